@@ -0,0 +1,330 @@
+use crate::output::serialize_millis;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use x_http::error::{Error, Result};
+use x_http::{HttpClient, Request};
+
+const HISTOGRAM_BUCKETS: usize = 10;
+
+#[derive(Debug, Clone)]
+pub struct BenchOptions {
+    pub concurrency: usize,
+    pub rate: Option<f64>,
+    pub duration: Option<Duration>,
+    pub requests: Option<usize>,
+}
+
+#[derive(Debug)]
+struct Sample {
+    latency: Duration,
+    status: Option<u16>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Latency {
+    #[serde(rename = "min_ms", serialize_with = "serialize_millis")]
+    pub min: Duration,
+    #[serde(rename = "mean_ms", serialize_with = "serialize_millis")]
+    pub mean: Duration,
+    #[serde(rename = "p50_ms", serialize_with = "serialize_millis")]
+    pub p50: Duration,
+    #[serde(rename = "p90_ms", serialize_with = "serialize_millis")]
+    pub p90: Duration,
+    #[serde(rename = "p99_ms", serialize_with = "serialize_millis")]
+    pub p99: Duration,
+    #[serde(rename = "max_ms", serialize_with = "serialize_millis")]
+    pub max: Duration,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Bucket {
+    #[serde(rename = "from_ms", serialize_with = "serialize_millis")]
+    pub from: Duration,
+    #[serde(rename = "to_ms", serialize_with = "serialize_millis")]
+    pub to: Duration,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BenchReport {
+    pub requests: usize,
+    pub errors: usize,
+    #[serde(rename = "duration_ms", serialize_with = "serialize_millis")]
+    pub elapsed: Duration,
+    pub throughput: f64,
+    pub latency: Latency,
+    pub status_codes: BTreeMap<u16, usize>,
+    pub error_messages: BTreeMap<String, usize>,
+    pub histogram: Vec<Bucket>,
+}
+
+pub fn run<F>(build: F, options: &BenchOptions) -> Result<BenchReport>
+where
+    F: Fn() -> Result<Request> + Sync,
+{
+    if options.rate.is_some_and(|rate| rate <= 0.0) {
+        return Err(Error::Config(
+            "--rate must be greater than zero".to_string(),
+        ));
+    }
+    let request = build()?;
+    let clients = (0..options.concurrency.max(1))
+        .map(|_| HttpClient::new(&request))
+        .collect::<Result<Vec<_>>>()?;
+
+    let schedule = Schedule {
+        issued: AtomicUsize::new(0),
+        start: Instant::now(),
+        deadline: options.duration.map(|duration| Instant::now() + duration),
+        options,
+    };
+
+    let samples: Vec<Sample> = thread::scope(|scope| {
+        let workers: Vec<_> = clients
+            .iter()
+            .map(|client| {
+                let (build, schedule) = (&build, &schedule);
+                scope.spawn(move || {
+                    let mut samples = Vec::new();
+                    while schedule.next() {
+                        samples.push(send_one(build, client));
+                    }
+                    samples
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("bench worker panicked"))
+            .collect()
+    });
+
+    Ok(BenchReport::from_samples(samples, schedule.start.elapsed()))
+}
+
+struct Schedule<'a> {
+    issued: AtomicUsize,
+    start: Instant,
+    deadline: Option<Instant>,
+    options: &'a BenchOptions,
+}
+
+impl Schedule<'_> {
+    fn next(&self) -> bool {
+        let sequence = self.issued.fetch_add(1, Ordering::SeqCst);
+        if self.options.requests.is_some_and(|limit| sequence >= limit) {
+            return false;
+        }
+
+        if let Some(rate) = self.options.rate {
+            let scheduled = self.start + Duration::from_secs_f64(sequence as f64 / rate);
+            if self.deadline.is_some_and(|deadline| scheduled >= deadline) {
+                return false;
+            }
+            thread::sleep(scheduled.saturating_duration_since(Instant::now()));
+        }
+
+        self.deadline
+            .is_none_or(|deadline| Instant::now() < deadline)
+    }
+}
+
+fn send_one<F: Fn() -> Result<Request>>(build: &F, client: &HttpClient) -> Sample {
+    let started = Instant::now();
+    match build().and_then(|request| request.send_with(client)) {
+        Ok(response) => Sample {
+            latency: response.duration(),
+            status: Some(response.status()),
+            error: None,
+        },
+        Err(e) => Sample {
+            latency: started.elapsed(),
+            status: None,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+impl BenchReport {
+    fn from_samples(samples: Vec<Sample>, elapsed: Duration) -> Self {
+        let mut status_codes = BTreeMap::new();
+        let mut error_messages = BTreeMap::new();
+        let mut latencies = Vec::with_capacity(samples.len());
+
+        for sample in samples {
+            latencies.push(sample.latency);
+            if let Some(status) = sample.status {
+                *status_codes.entry(status).or_insert(0) += 1;
+            }
+            if let Some(error) = sample.error {
+                *error_messages.entry(error).or_insert(0) += 1;
+            }
+        }
+        latencies.sort_unstable();
+
+        let requests = latencies.len();
+        let total: Duration = latencies.iter().sum();
+        let latency = Latency {
+            min: latencies.first().copied().unwrap_or_default(),
+            mean: total.checked_div(requests as u32).unwrap_or_default(),
+            p50: percentile(&latencies, 50.0),
+            p90: percentile(&latencies, 90.0),
+            p99: percentile(&latencies, 99.0),
+            max: latencies.last().copied().unwrap_or_default(),
+        };
+
+        Self {
+            requests,
+            errors: error_messages.values().sum(),
+            elapsed,
+            throughput: requests as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+            latency,
+            status_codes,
+            error_messages,
+            histogram: histogram(&latencies, HISTOGRAM_BUCKETS),
+        }
+    }
+}
+
+fn percentile(sorted: &[Duration], percent: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (percent / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn histogram(sorted: &[Duration], buckets: usize) -> Vec<Bucket> {
+    let (Some(&min), Some(&max)) = (sorted.first(), sorted.last()) else {
+        return Vec::new();
+    };
+    let width = (max - min).as_secs_f64() / buckets as f64;
+    if width == 0.0 {
+        return vec![Bucket {
+            from: min,
+            to: max,
+            count: sorted.len(),
+        }];
+    }
+
+    let mut histogram: Vec<Bucket> = (0..buckets)
+        .map(|i| Bucket {
+            from: min + Duration::from_secs_f64(width * i as f64),
+            to: min + Duration::from_secs_f64(width * (i + 1) as f64),
+            count: 0,
+        })
+        .collect();
+    for latency in sorted {
+        let index = ((*latency - min).as_secs_f64() / width) as usize;
+        histogram[index.min(buckets - 1)].count += 1;
+    }
+    histogram
+}
+
+pub fn parse_duration(text: &str) -> Result<Duration> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let value: f64 = number
+        .parse()
+        .map_err(|_| Error::Config(format!("Invalid duration: {}", text)))?;
+
+    let seconds = match unit {
+        "ms" => value / 1000.0,
+        "" | "s" => value,
+        "m" => value * 60.0,
+        "h" => value * 3600.0,
+        _ => return Err(Error::Config(format!("Invalid duration unit: {}", text))),
+    };
+    Ok(Duration::from_secs_f64(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(millis: u64, status: Option<u16>) -> Sample {
+        Sample {
+            latency: Duration::from_millis(millis),
+            status,
+            error: status.is_none().then(|| "connection refused".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_report_from_samples() {
+        let mut samples: Vec<Sample> = (1..=100).map(|ms| sample(ms, Some(200))).collect();
+        samples[0].status = Some(503);
+        samples.push(sample(5, None));
+
+        let report = BenchReport::from_samples(samples, Duration::from_secs(2));
+
+        assert_eq!(report.requests, 101);
+        assert_eq!(report.errors, 1);
+        assert_eq!(report.status_codes[&200], 99);
+        assert_eq!(report.status_codes[&503], 1);
+        assert_eq!(report.error_messages["connection refused"], 1);
+        assert!((report.throughput - 50.5).abs() < 1e-9);
+        assert_eq!(report.latency.min, Duration::from_millis(1));
+        assert_eq!(report.latency.p50, Duration::from_millis(50));
+        assert_eq!(report.latency.p99, Duration::from_millis(99));
+        assert_eq!(report.latency.max, Duration::from_millis(100));
+
+        assert_eq!(report.histogram.len(), HISTOGRAM_BUCKETS);
+        assert_eq!(report.histogram.iter().map(|b| b.count).sum::<usize>(), 101);
+        assert_eq!(report.histogram[9].count, 10);
+    }
+
+    #[test]
+    fn test_run_against_mock_server() {
+        use x_http::{MockServer, Stub};
+
+        let server = MockServer::start().unwrap();
+        let ok = server.mock(Stub::get("/ok"));
+        let busy = server.mock(Stub::get("/busy").status(503));
+
+        let sent = AtomicUsize::new(0);
+        let options = BenchOptions {
+            concurrency: 3,
+            rate: None,
+            duration: None,
+            requests: Some(12),
+        };
+        let report = run(
+            || {
+                let path = match sent.fetch_add(1, Ordering::SeqCst) % 3 {
+                    0 => "/busy",
+                    _ => "/ok",
+                };
+                Ok(Request::get(server.url(path)))
+            },
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(report.requests, 12);
+        assert_eq!(report.errors, 0);
+        assert_eq!(report.status_codes[&200], 8);
+        assert_eq!(report.status_codes[&503], 4);
+        ok.expect_called(8).unwrap();
+        busy.expect_called(4).unwrap();
+        assert_eq!(report.histogram.iter().map(|b| b.count).sum::<usize>(), 12);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration("250ms").unwrap(), Duration::from_millis(250));
+        assert_eq!(parse_duration("2m").unwrap(), Duration::from_secs(120));
+        assert_eq!(parse_duration("5").unwrap(), Duration::from_secs(5));
+        assert!(parse_duration("ten").is_err());
+        assert!(parse_duration("3d").is_err());
+    }
+}
@@ -0,0 +1,619 @@
+use crate::error::{Error, Result};
+use crate::request::Request;
+use crate::response::Response;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+pub const REDACTED: &str = "[REDACTED]";
+pub const DEFAULT_SCRUBBED_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "x-api-key",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    Record,
+    Replay,
+    Auto,
+}
+
+impl CassetteMode {
+    pub fn from_env() -> Self {
+        match std::env::var("X_HTTP_CASSETTE").as_deref() {
+            Ok("record") => CassetteMode::Record,
+            Ok("replay") => CassetteMode::Replay,
+            _ => CassetteMode::Auto,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, Vec<String>>,
+    #[serde(default, flatten)]
+    pub body: RecordedBody,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, Vec<String>>,
+    #[serde(default, flatten)]
+    pub body: RecordedBody,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecordedBody {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_base64: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CassetteFile {
+    #[serde(default)]
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug)]
+struct State {
+    mode: Option<CassetteMode>,
+    interactions: Vec<Interaction>,
+    played: Vec<bool>,
+    dirty: bool,
+}
+
+#[derive(Debug)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    match_method: bool,
+    match_url: bool,
+    match_body: bool,
+    match_headers: Vec<String>,
+    scrub_headers: Vec<String>,
+    scrub_query: Vec<String>,
+    scrub_secrets: Vec<(String, String)>,
+    state: Mutex<State>,
+}
+
+impl Cassette {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            mode: CassetteMode::from_env(),
+            match_method: true,
+            match_url: true,
+            match_body: false,
+            match_headers: Vec::new(),
+            scrub_headers: DEFAULT_SCRUBBED_HEADERS
+                .iter()
+                .map(|h| h.to_string())
+                .collect(),
+            scrub_query: Vec::new(),
+            scrub_secrets: Vec::new(),
+            state: Mutex::new(State {
+                mode: None,
+                interactions: Vec::new(),
+                played: Vec::new(),
+                dirty: false,
+            }),
+        }
+    }
+
+    pub fn mode(mut self, mode: CassetteMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn match_method(mut self, enabled: bool) -> Self {
+        self.match_method = enabled;
+        self
+    }
+
+    pub fn match_url(mut self, enabled: bool) -> Self {
+        self.match_url = enabled;
+        self
+    }
+
+    pub fn match_body(mut self, enabled: bool) -> Self {
+        self.match_body = enabled;
+        self
+    }
+
+    pub fn match_header(mut self, name: &str) -> Self {
+        self.match_headers.push(name.to_lowercase());
+        self
+    }
+
+    pub fn scrub_header(mut self, name: &str) -> Self {
+        self.scrub_headers.push(name.to_lowercase());
+        self
+    }
+
+    pub fn scrub_query_param(mut self, name: &str) -> Self {
+        self.scrub_query.push(name.to_string());
+        self
+    }
+
+    pub fn scrub_secret(
+        mut self,
+        secret: impl Into<String>,
+        placeholder: impl Into<String>,
+    ) -> Self {
+        let secret = secret.into();
+        if !secret.is_empty() {
+            self.scrub_secrets.push((secret, placeholder.into()));
+        }
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn send(&self, request: Request) -> Result<Response> {
+        let request = request.buffer_body()?;
+        let recorded = self.scrub_request(RecordedRequest::from_request(&request)?);
+
+        if self.resolved_mode()? == CassetteMode::Replay {
+            return self.replay(&recorded);
+        }
+
+        let response = request.send()?;
+        let interaction = Interaction {
+            request: recorded,
+            response: self.scrub_response(RecordedResponse::from_response(&response)),
+        };
+
+        let mut state = self.lock();
+        state.interactions.push(interaction);
+        state.played.push(true);
+        state.dirty = true;
+
+        Ok(response)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let mut state = self.lock();
+        if !state.dirty {
+            return Ok(());
+        }
+
+        let file = CassetteFile {
+            interactions: state.interactions.clone(),
+        };
+        let contents = if self.is_json() {
+            serde_json::to_string_pretty(&file)?
+        } else {
+            serde_yaml::to_string(&file)
+                .map_err(|e| Error::Cassette(format!("Failed to serialize cassette: {}", e)))?
+        };
+
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, contents)?;
+        state.dirty = false;
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<()> {
+        self.save()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn is_json(&self) -> bool {
+        self.path.extension().and_then(|e| e.to_str()) == Some("json")
+    }
+
+    fn resolved_mode(&self) -> Result<CassetteMode> {
+        let mut state = self.lock();
+        if let Some(mode) = state.mode {
+            return Ok(mode);
+        }
+
+        let mode = match self.mode {
+            CassetteMode::Auto if self.path.exists() => CassetteMode::Replay,
+            CassetteMode::Auto => CassetteMode::Record,
+            mode => mode,
+        };
+
+        if mode == CassetteMode::Replay {
+            let contents = fs::read_to_string(&self.path).map_err(|e| {
+                Error::Cassette(format!("Failed to read {}: {}", self.path.display(), e))
+            })?;
+            let file: CassetteFile = if self.is_json() {
+                serde_json::from_str(&contents)?
+            } else {
+                serde_yaml::from_str(&contents).map_err(|e| {
+                    Error::Cassette(format!("Failed to parse {}: {}", self.path.display(), e))
+                })?
+            };
+            state.played = vec![false; file.interactions.len()];
+            state.interactions = file.interactions;
+        }
+
+        state.mode = Some(mode);
+        Ok(mode)
+    }
+
+    fn replay(&self, request: &RecordedRequest) -> Result<Response> {
+        let mut state = self.lock();
+        let matching: Vec<usize> = state
+            .interactions
+            .iter()
+            .enumerate()
+            .filter(|(_, interaction)| self.matches(&interaction.request, request))
+            .map(|(index, _)| index)
+            .collect();
+
+        let index = matching
+            .iter()
+            .copied()
+            .find(|index| !state.played[*index])
+            .or_else(|| matching.last().copied())
+            .ok_or_else(|| {
+                Error::Cassette(format!(
+                    "No recorded interaction in {} matches {} {}",
+                    self.path.display(),
+                    request.method,
+                    request.url
+                ))
+            })?;
+
+        state.played[index] = true;
+        state.interactions[index].response.to_response()
+    }
+
+    fn matches(&self, recorded: &RecordedRequest, request: &RecordedRequest) -> bool {
+        (!self.match_method || recorded.method.eq_ignore_ascii_case(&request.method))
+            && (!self.match_url || recorded.url == request.url)
+            && (!self.match_body || recorded.body == request.body)
+            && self
+                .match_headers
+                .iter()
+                .all(|name| recorded.headers.get(name) == request.headers.get(name))
+    }
+
+    fn scrub_request(&self, mut request: RecordedRequest) -> RecordedRequest {
+        request.url = self.scrub_url(&request.url);
+        self.scrub_headers(&mut request.headers);
+        self.scrub_body(&mut request.body);
+        request
+    }
+
+    fn scrub_response(&self, mut response: RecordedResponse) -> RecordedResponse {
+        self.scrub_headers(&mut response.headers);
+        self.scrub_body(&mut response.body);
+        response
+    }
+
+    fn scrub_url(&self, url: &str) -> String {
+        let mut url = self.scrub_text(url);
+        if self.scrub_query.is_empty() {
+            return url;
+        }
+
+        if let Ok(mut parsed) = url::Url::parse(&url) {
+            let pairs: Vec<(String, String)> = parsed
+                .query_pairs()
+                .map(|(key, value)| {
+                    if self.scrub_query.iter().any(|name| *name == key) {
+                        (key.into_owned(), REDACTED.to_string())
+                    } else {
+                        (key.into_owned(), value.into_owned())
+                    }
+                })
+                .collect();
+            if !pairs.is_empty() {
+                parsed.query_pairs_mut().clear().extend_pairs(pairs);
+            }
+            url = parsed.to_string();
+        }
+        url
+    }
+
+    fn scrub_headers(&self, headers: &mut BTreeMap<String, Vec<String>>) {
+        for (name, values) in headers.iter_mut() {
+            let redact = self.scrub_headers.contains(name);
+            for value in values.iter_mut() {
+                *value = if redact {
+                    REDACTED.to_string()
+                } else {
+                    self.scrub_text(value)
+                };
+            }
+        }
+    }
+
+    fn scrub_body(&self, body: &mut RecordedBody) {
+        if let Some(text) = &mut body.body {
+            *text = self.scrub_text(text);
+        }
+    }
+
+    fn scrub_text(&self, text: &str) -> String {
+        self.scrub_secrets
+            .iter()
+            .fold(text.to_string(), |text, (secret, placeholder)| {
+                text.replace(secret, placeholder)
+            })
+    }
+}
+
+impl Drop for Cassette {
+    fn drop(&mut self) {
+        let _ = self.save();
+    }
+}
+
+impl RecordedRequest {
+    fn from_request(request: &Request) -> Result<Self> {
+        Ok(Self {
+            method: request.method().as_str().to_string(),
+            url: normalize_url(request.full_url()?),
+            headers: header_values(request.header_map()),
+            body: request
+                .body_bytes()
+                .map(RecordedBody::new)
+                .unwrap_or_default(),
+        })
+    }
+}
+
+impl RecordedResponse {
+    fn from_response(response: &Response) -> Self {
+        Self {
+            status: response.status(),
+            headers: header_values(response.headers()),
+            body: RecordedBody::new(response.body_bytes()),
+        }
+    }
+
+    fn to_response(&self) -> Result<Response> {
+        let mut headers = HeaderMap::new();
+        for (name, values) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| Error::Cassette(format!("Invalid header name '{}': {}", name, e)))?;
+            for value in values {
+                let value = HeaderValue::from_str(value).map_err(|e| {
+                    Error::Cassette(format!("Invalid value for header '{}': {}", name, e))
+                })?;
+                headers.append(name.clone(), value);
+            }
+        }
+
+        let body = match (&self.body.body, &self.body.body_base64) {
+            (_, Some(encoded)) => STANDARD
+                .decode(encoded)
+                .map_err(|e| Error::Cassette(format!("Invalid base64 body: {}", e)))?,
+            (Some(text), None) => text.clone().into_bytes(),
+            (None, None) => Vec::new(),
+        };
+
+        let status = StatusCode::from_u16(self.status)
+            .map_err(|_| Error::Cassette(format!("Invalid recorded status {}", self.status)))?;
+        Ok(Response::from_parts(status, headers, body))
+    }
+}
+
+impl RecordedBody {
+    fn new(bytes: &[u8]) -> Self {
+        if bytes.is_empty() {
+            return Self::default();
+        }
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Self {
+                body: Some(text.to_string()),
+                body_base64: None,
+            },
+            Err(_) => Self {
+                body: None,
+                body_base64: Some(STANDARD.encode(bytes)),
+            },
+        }
+    }
+}
+
+fn normalize_url(mut url: url::Url) -> String {
+    let mut pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    if pairs.len() > 1 {
+        pairs.sort();
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    url.to_string()
+}
+
+fn header_values(headers: &HeaderMap) -> BTreeMap<String, Vec<String>> {
+    let mut values: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (name, value) in headers {
+        values
+            .entry(name.as_str().to_string())
+            .or_default()
+            .push(String::from_utf8_lossy(value.as_bytes()).into_owned());
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::Session;
+
+    const CASSETTE: &str = r#"
+interactions:
+  - request:
+      method: GET
+      url: https://api.example.com/users?page=1
+    response:
+      status: 200
+      headers:
+        content-type:
+          - application/json
+      body: '{"users": [{"id": 1}]}'
+  - request:
+      method: POST
+      url: https://api.example.com/users
+      body: '{"name":"John"}'
+    response:
+      status: 201
+      body: '{"id": 2}'
+"#;
+
+    #[test]
+    fn test_replay_without_network() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("users.yaml");
+        fs::write(&path, CASSETTE).unwrap();
+
+        let session = Session::new().cassette(
+            Cassette::new(&path)
+                .mode(CassetteMode::Replay)
+                .match_body(true),
+        );
+
+        session
+            .send(Request::get("https://api.example.com/users").query("page", "1"))
+            .unwrap()
+            .expect_status(200)
+            .unwrap()
+            .assert_field("users[0].id", 1)
+            .unwrap();
+
+        let created = session
+            .send(
+                Request::post("https://api.example.com/users")
+                    .json(&serde_json::json!({"name": "John"}))
+                    .unwrap(),
+            )
+            .unwrap();
+        assert_eq!(created.status(), 201);
+
+        let body_path = dir.path().join("user.json");
+        fs::write(&body_path, r#"{"name":"John"}"#).unwrap();
+        let from_file = session
+            .send(Request::post("https://api.example.com/users").body_file(&body_path))
+            .unwrap();
+        assert_eq!(from_file.status(), 201);
+
+        let from_reader = session
+            .send(
+                Request::post("https://api.example.com/users")
+                    .body_reader(r#"{"name":"John"}"#.as_bytes()),
+            )
+            .unwrap();
+        assert_eq!(from_reader.status(), 201);
+
+        let miss = session
+            .send(Request::post("https://api.example.com/users").text("other"))
+            .unwrap_err();
+        assert!(miss.to_string().contains("No recorded interaction"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_record_then_replay() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/users"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("[]"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let url = format!("{}/users", server.uri());
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cassettes/users.json");
+
+        tokio::task::spawn_blocking(move || {
+            let recording =
+                Session::new().cassette(Cassette::new(&path).mode(CassetteMode::Record));
+            recording
+                .send(Request::get(&url).header("Authorization", "Bearer t0ken"))
+                .unwrap();
+            recording.finish().unwrap();
+
+            let saved = fs::read_to_string(&path).unwrap();
+            assert!(saved.contains(REDACTED));
+            assert!(!saved.contains("t0ken"));
+
+            let replaying =
+                Session::new().cassette(Cassette::new(&path).mode(CassetteMode::Replay));
+            let response = replaying.send(Request::get(&url)).unwrap();
+            assert_eq!(response.text().unwrap(), "[]");
+        })
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn test_scrub_secrets_before_saving() {
+        let cassette = Cassette::new("unused.yaml")
+            .scrub_query_param("api_key")
+            .scrub_secret("s3cr3t", "<TOKEN>");
+
+        let request = Request::get("https://api.example.com/me?api_key=abc&b=2")
+            .header("Authorization", "Bearer xyz")
+            .header("X-Trace", "token=s3cr3t")
+            .text("password=s3cr3t");
+        let recorded = cassette.scrub_request(RecordedRequest::from_request(&request).unwrap());
+
+        assert_eq!(
+            recorded.url,
+            "https://api.example.com/me?api_key=%5BREDACTED%5D&b=2"
+        );
+        assert_eq!(recorded.headers["authorization"], vec![REDACTED]);
+        assert_eq!(recorded.headers["x-trace"], vec!["token=<TOKEN>"]);
+        assert_eq!(recorded.body.body.as_deref(), Some("password=<TOKEN>"));
+    }
+
+    #[test]
+    fn test_binary_bodies_round_trip() {
+        let response = RecordedResponse {
+            status: 200,
+            headers: BTreeMap::new(),
+            body: RecordedBody::new(&[0xff, 0x00, 0x10]),
+        };
+        assert!(response.body.body.is_none());
+        assert_eq!(
+            response.to_response().unwrap().body_bytes(),
+            &[0xff, 0x00, 0x10]
+        );
+
+        let invalid = RecordedResponse {
+            status: 42,
+            ..response
+        };
+        assert!(matches!(invalid.to_response(), Err(Error::Cassette(_))));
+    }
+}
@@ -0,0 +1,245 @@
+use crate::error::{Error, Result};
+use brotli::{CompressorReader, Decompressor};
+use flate2::read::{GzEncoder, MultiGzDecoder, ZlibDecoder, ZlibEncoder};
+use flate2::Compression;
+use std::io::Read;
+use std::str::FromStr;
+
+pub const ACCEPT_ENCODING: &str = "gzip, deflate, br, zstd";
+
+const BUFFER_SIZE: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Gzip,
+    Deflate,
+    Brotli,
+    Zstd,
+}
+
+impl Encoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Encoding::Gzip => "gzip",
+            Encoding::Deflate => "deflate",
+            Encoding::Brotli => "br",
+            Encoding::Zstd => "zstd",
+        }
+    }
+
+    pub fn encode(&self, bytes: &[u8]) -> Result<Vec<u8>> {
+        let mut encoded = Vec::new();
+        self.encoder(bytes)?.read_to_end(&mut encoded)?;
+        Ok(encoded)
+    }
+
+    pub fn decode(&self, bytes: &[u8]) -> Result<Vec<u8>> {
+        self.decode_limited(bytes, None)
+    }
+
+    pub(crate) fn encoder<'a>(
+        &self,
+        reader: impl Read + Send + 'a,
+    ) -> Result<Box<dyn Read + Send + 'a>> {
+        Ok(match self {
+            Encoding::Gzip => Box::new(GzEncoder::new(reader, Compression::default())),
+            Encoding::Deflate => Box::new(ZlibEncoder::new(reader, Compression::default())),
+            Encoding::Brotli => Box::new(CompressorReader::new(reader, BUFFER_SIZE, 5, 22)),
+            Encoding::Zstd => Box::new(zstd::stream::read::Encoder::new(reader, 0)?),
+        })
+    }
+
+    pub fn decoder<'a>(&self, reader: impl Read + 'a) -> Result<Box<dyn Read + 'a>> {
+        Ok(match self {
+            Encoding::Gzip => Box::new(MultiGzDecoder::new(reader)),
+            Encoding::Deflate => Box::new(ZlibDecoder::new(reader)),
+            Encoding::Brotli => Box::new(Decompressor::new(reader, BUFFER_SIZE)),
+            Encoding::Zstd => Box::new(
+                zstd::stream::read::Decoder::new(reader).map_err(|e| self.decode_error(e))?,
+            ),
+        })
+    }
+
+    fn decode_limited(&self, bytes: &[u8], limit: Option<u64>) -> Result<Vec<u8>> {
+        let mut decoded = Vec::new();
+        self.decoder(bytes)?
+            .take(limit.map_or(u64::MAX, |limit| limit + 1))
+            .read_to_end(&mut decoded)
+            .map_err(|e| self.decode_error(e))?;
+        if let Some(limit) = limit.filter(|&limit| decoded.len() as u64 > limit) {
+            return Err(Error::BodyTooLarge { limit });
+        }
+        Ok(decoded)
+    }
+
+    fn decode_error(&self, error: std::io::Error) -> Error {
+        Error::Compression(format!(
+            "Failed to decode {} body: {}",
+            self.as_str(),
+            error
+        ))
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "gzip" | "x-gzip" => Ok(Encoding::Gzip),
+            "deflate" => Ok(Encoding::Deflate),
+            "br" | "brotli" => Ok(Encoding::Brotli),
+            "zstd" => Ok(Encoding::Zstd),
+            _ => Err(Error::Compression(format!(
+                "Unsupported encoding '{}'",
+                value
+            ))),
+        }
+    }
+}
+
+pub fn content_encodings(content_encoding: &str) -> Option<Vec<Encoding>> {
+    let mut encodings = Vec::new();
+    for token in content_encoding.split(',').map(str::trim) {
+        if token.is_empty() || token.eq_ignore_ascii_case("identity") {
+            continue;
+        }
+        encodings.push(token.parse::<Encoding>().ok()?);
+    }
+    Some(encodings).filter(|encodings| !encodings.is_empty())
+}
+
+pub(crate) fn decode(
+    content_encoding: &str,
+    body: &[u8],
+    limit: Option<u64>,
+) -> Result<Option<Vec<u8>>> {
+    let Some(encodings) = content_encodings(content_encoding) else {
+        return Ok(None);
+    };
+
+    let mut decoded = body.to_vec();
+    for encoding in encodings.iter().rev() {
+        decoded = encoding.decode_limited(&decoded, limit)?;
+    }
+    Ok(Some(decoded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_and_stacked_encodings() {
+        let body = "hello compression ".repeat(100);
+        for encoding in [
+            Encoding::Gzip,
+            Encoding::Deflate,
+            Encoding::Brotli,
+            Encoding::Zstd,
+        ] {
+            let encoded = encoding.encode(body.as_bytes()).unwrap();
+            assert!(encoded.len() < body.len());
+            assert_eq!(encoding.decode(&encoded).unwrap(), body.as_bytes());
+            assert_eq!(encoding.as_str().parse::<Encoding>().unwrap(), encoding);
+        }
+
+        let stacked = Encoding::Brotli
+            .encode(&Encoding::Gzip.encode(body.as_bytes()).unwrap())
+            .unwrap();
+        let decoded = decode("gzip, br", &stacked, None).unwrap().unwrap();
+        assert_eq!(decoded, body.as_bytes());
+
+        assert!(decode("identity", b"raw", None).unwrap().is_none());
+        assert!(decode("compress", b"raw", None).unwrap().is_none());
+        assert!(matches!(
+            decode("gzip", b"not gzip", None),
+            Err(Error::Compression(_))
+        ));
+
+        let encoded = Encoding::Gzip.encode(body.as_bytes()).unwrap();
+        assert!(matches!(
+            decode("gzip", &encoded, Some(100)),
+            Err(Error::BodyTooLarge { limit: 100 })
+        ));
+    }
+
+    #[test]
+    fn test_compressed_exchange() {
+        use crate::mock::{MockServer, Stub};
+        use crate::request::Request;
+
+        let body = r#"{"message": "hello"}"#.repeat(50);
+        let gzipped = Encoding::Gzip.encode(body.as_bytes()).unwrap();
+        let server = MockServer::start().unwrap();
+        server.mock(
+            Stub::get("/data")
+                .header("Accept-Encoding", ACCEPT_ENCODING)
+                .response_header("Content-Encoding", "gzip")
+                .response_body(gzipped.clone()),
+        );
+        server.mock(Stub::post("/upload").status(202));
+
+        let response = Request::get(server.url("/data"))
+            .accept_compressed()
+            .send()
+            .unwrap()
+            .expect_compressed("gzip")
+            .unwrap();
+        assert_eq!(response.content_encoding(), Some("gzip"));
+        assert_eq!(response.body_bytes(), body.as_bytes());
+        assert_eq!(response.encoded_size(), gzipped.len());
+        assert_eq!(response.decoded_size(), body.len());
+
+        let raw = Request::get(server.url("/data"))
+            .accept_compressed()
+            .decompress(false)
+            .send()
+            .unwrap();
+        assert_eq!(raw.body_bytes(), gzipped.as_slice());
+        assert_eq!(raw.decoded_size(), body.len());
+        assert!(raw.expect_compressed("br").is_err());
+        assert!(matches!(
+            Request::get(server.url("/data"))
+                .accept_compressed()
+                .decompress(false)
+                .max_body_size(gzipped.len() as u64)
+                .send(),
+            Err(Error::BodyTooLarge { .. })
+        ));
+        assert!(matches!(
+            Request::post(server.url("/upload"))
+                .form_text("name", "John")
+                .compress_body(Encoding::Gzip)
+                .send(),
+            Err(Error::Config(_))
+        ));
+
+        Request::post(server.url("/upload"))
+            .text(body.clone())
+            .compress_body(Encoding::Zstd)
+            .send()
+            .unwrap();
+        Request::post(server.url("/upload"))
+            .body_reader(std::io::Cursor::new(body.clone().into_bytes()))
+            .compress_body(Encoding::Gzip)
+            .send()
+            .unwrap();
+
+        let uploads: Vec<_> = server
+            .received_requests()
+            .into_iter()
+            .filter(|request| request.path == "/upload")
+            .collect();
+        assert_eq!(uploads[0].headers.get("content-encoding").unwrap(), "zstd");
+        assert_eq!(
+            Encoding::Zstd.decode(&uploads[0].body).unwrap(),
+            body.as_bytes()
+        );
+        assert_eq!(uploads[1].headers.get("content-encoding").unwrap(), "gzip");
+        assert_eq!(
+            Encoding::Gzip.decode(&uploads[1].body).unwrap(),
+            body.as_bytes()
+        );
+    }
+}
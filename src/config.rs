@@ -1,28 +1,378 @@
 use crate::display;
+use crate::exit_status::ExitStatus;
+use crate::http_file;
+use crate::output::{self, AssertionOutcome, OutputFormat, RequestResult};
+use crate::report;
+use crate::schedule::{Plan, Step};
+use crate::ws;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
 use x_http::error::{Error, Result};
-use x_http::{Method, Request};
+use x_http::{html, xml, CookieJar, Har, Method, OpenApi, Request, Response, Session, Snapshot};
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct Config {
     #[serde(default)]
     pub variables: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub environments: HashMap<String, HashMap<String, String>>,
     #[serde(default)]
     pub requests: Vec<RequestConfig>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct RequestConfig {
     pub name: String,
     pub method: String,
     pub url: String,
     #[serde(default)]
     pub headers: HashMap<String, String>,
+    pub basic_auth: Option<BasicAuthConfig>,
     pub body: Option<String>,
+    pub body_file: Option<String>,
     #[serde(default)]
     pub json: bool,
+    pub compress: Option<String>,
+    pub soap_action: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub form: HashMap<String, String>,
+    pub graphql: Option<GraphqlConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ws: Vec<WsStep>,
+    pub timeout: Option<u64>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub insecure: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    #[serde(default, skip_serializing_if = "AssertionConfig::is_empty")]
+    pub assertions: AssertionConfig,
+    #[serde(default, skip_serializing_if = "CaptureConfig::is_empty")]
+    pub capture: CaptureConfig,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct BasicAuthConfig {
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct GraphqlConfig {
+    pub query: Option<String>,
+    pub file: Option<String>,
+    pub variables: Option<serde_json::Value>,
+    pub operation_name: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct WsStep {
+    pub send: Option<String>,
+    pub send_json: Option<serde_json::Value>,
+    pub send_binary: Option<String>,
+    pub expect: Option<String>,
+    pub expect_contains: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub expect_json: HashMap<String, serde_json::Value>,
+    pub expect_close: Option<u16>,
+    pub timeout_ms: Option<u64>,
+    pub close: Option<u16>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct AssertionConfig {
+    pub status: Option<u16>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub fields: HashMap<String, serde_json::Value>,
+    pub body_contains: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub cookies: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub cookie_flags: HashMap<String, String>,
+    pub compressed: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub xpath: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub xpath_count: HashMap<String, usize>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub namespaces: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub selector_exists: Vec<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub selector_text: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub selector_count: HashMap<String, usize>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub attributes: HashMap<String, HashMap<String, String>>,
+    pub schema: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub no_graphql_errors: bool,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub data: HashMap<String, serde_json::Value>,
+    pub snapshot: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redact: Vec<String>,
+}
+
+impl AssertionConfig {
+    pub fn is_empty(&self) -> bool {
+        self.status.is_none()
+            && self.headers.is_empty()
+            && self.fields.is_empty()
+            && self.body_contains.is_none()
+            && self.cookies.is_empty()
+            && self.cookie_flags.is_empty()
+            && self.compressed.is_none()
+            && self.xpath.is_empty()
+            && self.xpath_count.is_empty()
+            && self.selector_exists.is_empty()
+            && self.selector_text.is_empty()
+            && self.selector_count.is_empty()
+            && self.attributes.is_empty()
+            && self.schema.is_none()
+            && !self.no_graphql_errors
+            && self.data.is_empty()
+            && self.snapshot.is_none()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        self.xpath
+            .keys()
+            .chain(self.xpath_count.keys())
+            .try_for_each(|xpath| xml::validate_xpath(xpath))?;
+        self.selector_exists
+            .iter()
+            .chain(self.selector_text.keys())
+            .chain(self.selector_count.keys())
+            .chain(self.attributes.keys())
+            .try_for_each(|css| html::validate_selector(css))
+    }
+
+    pub fn evaluate(&self, response: &Response, snapshot: &Snapshot) -> Vec<AssertionOutcome> {
+        let mut outcomes = Vec::new();
+
+        if let Some(status) = self.status {
+            outcomes.push(AssertionOutcome::from_result(
+                format!("status is {}", status),
+                response.check_status(status),
+            ));
+        }
+        for (key, value) in &self.headers {
+            outcomes.push(AssertionOutcome::from_result(
+                format!("header '{}' is '{}'", key, value),
+                response.check_header(key, value),
+            ));
+        }
+        for (path, value) in &self.fields {
+            outcomes.push(AssertionOutcome::from_result(
+                format!("field '{}' is {}", path, value),
+                response.check_field(path, value),
+            ));
+        }
+        if let Some(text) = &self.body_contains {
+            outcomes.push(AssertionOutcome::from_result(
+                format!("body contains '{}'", text),
+                response.check_body_contains(text),
+            ));
+        }
+        for (name, value) in &self.cookies {
+            outcomes.push(AssertionOutcome::from_result(
+                format!("cookie '{}' is '{}'", name, value),
+                response.check_cookie(name, value),
+            ));
+        }
+        for (name, flags) in &self.cookie_flags {
+            outcomes.push(AssertionOutcome::from_result(
+                format!("cookie '{}' has {}", name, flags),
+                response.check_cookie_flags(name, flags),
+            ));
+        }
+        if let Some(encoding) = &self.compressed {
+            outcomes.push(AssertionOutcome::from_result(
+                format!("response is compressed with {}", encoding),
+                response.check_compressed(encoding),
+            ));
+        }
+        if !self.xpath.is_empty() || !self.xpath_count.is_empty() {
+            let document = response.xml().map(|document| {
+                self.namespaces
+                    .iter()
+                    .fold(document, |document, (prefix, uri)| {
+                        document.namespace(prefix, uri)
+                    })
+            });
+            for (xpath, value) in &self.xpath {
+                outcomes.push(AssertionOutcome::from_result(
+                    format!("xpath '{}' is '{}'", xpath, value),
+                    check_document(&document, |document| document.check_xpath(xpath, value)),
+                ));
+            }
+            for (xpath, count) in &self.xpath_count {
+                outcomes.push(AssertionOutcome::from_result(
+                    format!("xpath '{}' matches {} nodes", xpath, count),
+                    check_document(&document, |document| {
+                        document.check_xpath_count(xpath, *count)
+                    }),
+                ));
+            }
+        }
+        if !self.selector_exists.is_empty()
+            || !self.selector_text.is_empty()
+            || !self.selector_count.is_empty()
+            || !self.attributes.is_empty()
+        {
+            let document = response.html();
+            for css in &self.selector_exists {
+                outcomes.push(AssertionOutcome::from_result(
+                    format!("selector '{}' exists", css),
+                    check_document(&document, |document| document.check_selector_exists(css)),
+                ));
+            }
+            for (css, text) in &self.selector_text {
+                outcomes.push(AssertionOutcome::from_result(
+                    format!("selector '{}' has text '{}'", css, text),
+                    check_document(&document, |document| {
+                        document.check_selector_text(css, text)
+                    }),
+                ));
+            }
+            for (css, count) in &self.selector_count {
+                outcomes.push(AssertionOutcome::from_result(
+                    format!("selector '{}' matches {} elements", css, count),
+                    check_document(&document, |document| {
+                        document.check_selector_count(css, *count)
+                    }),
+                ));
+            }
+            for (css, attributes) in &self.attributes {
+                for (attribute, value) in attributes {
+                    outcomes.push(AssertionOutcome::from_result(
+                        format!("selector '{}' has {}='{}'", css, attribute, value),
+                        check_document(&document, |document| {
+                            document.check_attribute(css, attribute, value)
+                        }),
+                    ));
+                }
+            }
+        }
+        if let Some(schema) = &self.schema {
+            outcomes.push(AssertionOutcome::from_result(
+                "body matches schema",
+                response.check_schema(schema),
+            ));
+        }
+        if self.no_graphql_errors {
+            outcomes.push(AssertionOutcome::from_result(
+                "no GraphQL errors",
+                response.check_no_graphql_errors(),
+            ));
+        }
+        for (path, value) in &self.data {
+            outcomes.push(AssertionOutcome::from_result(
+                format!("data '{}' is {}", path, value),
+                response.check_data_field(path, value),
+            ));
+        }
+        if let Some(name) = &self.snapshot {
+            let snapshot = self
+                .redact
+                .iter()
+                .fold(snapshot.clone(), |snapshot, path| snapshot.redact(path));
+            outcomes.push(AssertionOutcome::from_result(
+                format!("body matches snapshot '{}'", name),
+                snapshot.assert(name, response),
+            ));
+        }
+
+        outcomes
+    }
+}
+
+fn check_document<D>(
+    document: &Result<D>,
+    check: impl FnOnce(&D) -> Result<()>,
+) -> std::result::Result<(), String> {
+    let document = document.as_ref().map_err(Error::to_string)?;
+    check(document).map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct CaptureConfig {
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub html: HashMap<String, String>,
+    pub form: Option<String>,
+    pub csrf: Option<String>,
+}
+
+impl CaptureConfig {
+    pub fn is_empty(&self) -> bool {
+        self.html.is_empty() && self.form.is_none() && self.csrf.is_none()
+    }
+
+    pub fn apply(&self, response: &Response) -> (HashMap<String, String>, Vec<AssertionOutcome>) {
+        let mut captured = HashMap::new();
+        let mut outcomes = Vec::new();
+        if self.is_empty() {
+            return (captured, outcomes);
+        }
+
+        let document = match response.html() {
+            Ok(document) => document,
+            Err(e) => {
+                outcomes.push(AssertionOutcome::from_result::<(), _>(
+                    "captured HTML",
+                    Err(e),
+                ));
+                return (captured, outcomes);
+            }
+        };
+
+        for (name, target) in &self.html {
+            let value = match target.rsplit_once('@') {
+                Some((css, attribute)) => document.attribute(css, attribute),
+                None => document.text(target),
+            }
+            .and_then(|value| {
+                value.ok_or_else(|| Error::PathNotFound {
+                    path: target.clone(),
+                })
+            });
+            outcomes.push(AssertionOutcome::from_result(
+                format!("captured '{}'", name),
+                value.map(|value| captured.insert(name.clone(), value)),
+            ));
+        }
+        if let Some(form) = &self.form {
+            outcomes.push(AssertionOutcome::from_result(
+                format!("captured fields of '{}'", form),
+                document.form_fields(form).map(|fields| {
+                    captured.extend(
+                        fields
+                            .into_iter()
+                            .map(|(name, value)| (format!("{}.{}", form, name), value)),
+                    )
+                }),
+            ));
+        }
+        if let Some(name) = &self.csrf {
+            let token = document
+                .csrf_token()
+                .ok_or_else(|| Error::Html("No CSRF token found".to_string()));
+            outcomes.push(AssertionOutcome::from_result(
+                format!("captured '{}'", name),
+                token.map(|token| captured.insert(name.clone(), token)),
+            ));
+        }
+
+        (captured, outcomes)
+    }
 }
 
 impl Config {
@@ -30,10 +380,30 @@ impl Config {
         let contents = fs::read_to_string(path)
             .map_err(|e| Error::Config(format!("Failed to read config file: {}", e)))?;
 
+        if http_file::is_http_file(path) {
+            return http_file::parse(&contents);
+        }
+
         toml::from_str(&contents)
             .map_err(|e| Error::Config(format!("Failed to parse config file: {}", e)))
     }
 
+    pub fn save(&self, path: &str) -> Result<()> {
+        let contents = toml::to_string_pretty(self)
+            .map_err(|e| Error::Config(format!("Failed to serialize config: {}", e)))?;
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    pub fn use_environment(&mut self, name: &str) -> Result<()> {
+        let environment = self
+            .environments
+            .get(name)
+            .ok_or_else(|| Error::Config(format!("Unknown environment '{}'", name)))?;
+        self.variables.extend(environment.clone());
+        Ok(())
+    }
+
     pub fn substitute_variables(&self, text: &str) -> String {
         let mut result = text.to_string();
         for (key, value) in &self.variables {
@@ -42,41 +412,282 @@ impl Config {
         }
         result
     }
+
+    pub fn substitute_json(&self, value: &serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::String(text) => {
+                serde_json::Value::String(self.substitute_variables(text))
+            }
+            serde_json::Value::Array(items) => {
+                serde_json::Value::Array(items.iter().map(|v| self.substitute_json(v)).collect())
+            }
+            serde_json::Value::Object(map) => serde_json::Value::Object(
+                map.iter()
+                    .map(|(key, v)| (key.clone(), self.substitute_json(v)))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    pub fn with_captured(&self, captured: &HashMap<String, String>) -> Config {
+        let mut variables = self.variables.clone();
+        variables.extend(captured.clone());
+        Config {
+            variables,
+            ..Config::default()
+        }
+    }
 }
 
-pub fn run_from_config(config_path: &str, request_name: Option<&str>) -> Result<()> {
-    let config = Config::load(config_path)?;
+#[derive(Default)]
+pub struct RunOptions {
+    pub contract: Option<OpenApi>,
+    pub print_curl: bool,
+    pub output: OutputFormat,
+    pub report: Option<String>,
+    pub fail_on_http_error: bool,
+    pub parallel: usize,
+    pub har: Option<String>,
+    pub update_snapshots: bool,
+    pub cookie_jar: Option<String>,
+    pub environment: Option<String>,
+}
 
-    let requests_to_run: Vec<&RequestConfig> = if let Some(name) = request_name {
-        config.requests.iter().filter(|r| r.name == name).collect()
-    } else {
-        config.requests.iter().collect()
+struct Execution {
+    result: RequestResult,
+    response: Option<Response>,
+    curl: Option<String>,
+    warnings: Vec<String>,
+    captured: HashMap<String, String>,
+}
+
+impl Execution {
+    fn new(config: &Config, request_config: &RequestConfig) -> Self {
+        let url = config.substitute_variables(&request_config.url);
+        Self {
+            result: RequestResult::new(&request_config.name, &request_config.method, &url),
+            response: None,
+            curl: None,
+            warnings: Vec::new(),
+            captured: HashMap::new(),
+        }
+    }
+}
+
+pub fn run_from_config(
+    config_path: &str,
+    request_name: Option<&str>,
+    options: &RunOptions,
+) -> Result<ExitStatus> {
+    let mut config = Config::load(config_path)?;
+    if let Some(environment) = &options.environment {
+        config.use_environment(environment)?;
+    }
+    let plan = Plan::new(&config.requests, request_name)?;
+    let snapshot_dir = Path::new(config_path)
+        .parent()
+        .unwrap_or(Path::new(""))
+        .join("snapshots");
+    let mut snapshot = Snapshot::new().dir(snapshot_dir);
+    if options.update_snapshots {
+        snapshot = snapshot.update(true);
+    }
+    let jar = options
+        .cookie_jar
+        .as_ref()
+        .map(CookieJar::load)
+        .transpose()?;
+    let session = match &jar {
+        Some(jar) => Session::new().cookie_jar(jar.clone()),
+        None => Session::new(),
     };
 
-    if requests_to_run.is_empty() {
-        return Err(Error::Config(format!(
-            "No requests found{}",
-            request_name.map_or(String::new(), |n| format!(" with name '{}'", n))
-        )));
+    let started = Instant::now();
+    let executions = run_plan(&config, plan, options, &session, &snapshot)?;
+    let elapsed = started.elapsed();
+
+    if let (Some(jar), Some(path)) = (&jar, &options.cookie_jar) {
+        jar.save(path)?;
+    }
+
+    if let Some(path) = &options.har {
+        let mut har = Har::new();
+        for response in executions.iter().filter_map(|e| e.response.as_ref()) {
+            har.add(response);
+        }
+        har.save(path)?;
+        if options.output.is_human() {
+            println!("\n📦 HAR written to {}", path);
+        }
+    }
+
+    let results: Vec<RequestResult> = executions.into_iter().map(|e| e.result).collect();
+
+    output::emit_results(&results, options.output)?;
+
+    if let Some(path) = &options.report {
+        report::write_html(&results, path)?;
+        if options.output.is_human() {
+            println!("\n📄 Report written to {}", path);
+        }
     }
 
-    for request_config in requests_to_run {
-        println!("\n🚀 Running: {}", request_config.name);
-        execute_request_config(&config, request_config)?;
+    if options.output.is_human() {
+        display::display_summary(&results, elapsed);
+    }
+
+    Ok(results
+        .iter()
+        .map(RequestResult::exit_status)
+        .max()
+        .unwrap_or_default())
+}
+
+fn run_plan(
+    config: &Config,
+    mut plan: Plan,
+    options: &RunOptions,
+    session: &Session,
+    snapshot: &Snapshot,
+) -> Result<Vec<Execution>> {
+    let parallel = options.parallel.max(1);
+    let (sender, receiver) = mpsc::channel();
+    let mut executions = Vec::new();
+    let mut captured = HashMap::new();
+
+    thread::scope(|scope| -> Result<()> {
+        let mut running = 0;
+        loop {
+            while running < parallel {
+                match plan.next() {
+                    Some(Step::Run(index)) => {
+                        let request_config = &config.requests[index];
+                        if parallel == 1 && options.output.is_human() {
+                            println!("\n🚀 Running: {}", request_config.name);
+                        }
+                        let sender = sender.clone();
+                        let scoped =
+                            (!captured.is_empty()).then(|| config.with_captured(&captured));
+                        scope.spawn(move || {
+                            let config = scoped.as_ref().unwrap_or(config);
+                            let execution = execute_request_config(
+                                config,
+                                request_config,
+                                options,
+                                session,
+                                snapshot,
+                            );
+                            let _ = sender.send((index, execution));
+                        });
+                        running += 1;
+                    }
+                    Some(Step::Skip { index, dependency }) => {
+                        let mut execution = Execution::new(config, &config.requests[index]);
+                        execution.result.error = Some(format!(
+                            "Skipped because dependency '{}' failed",
+                            config.requests[dependency].name
+                        ));
+                        report_execution(&execution, options, true)?;
+                        executions.push((index, execution));
+                    }
+                    None => break,
+                }
+            }
+
+            if running == 0 {
+                return Ok(());
+            }
+
+            let (index, execution) = receiver
+                .recv()
+                .map_err(|e| Error::Config(format!("Request worker stopped: {}", e)))?;
+            running -= 1;
+            captured.extend(execution.captured.clone());
+            plan.complete(index, execution.result.passed());
+            report_execution(&execution, options, parallel > 1)?;
+            executions.push((index, execution));
+        }
+    })?;
+
+    executions.sort_by_key(|(index, _)| *index);
+    Ok(executions
+        .into_iter()
+        .map(|(_, execution)| execution)
+        .collect())
+}
+
+fn report_execution(execution: &Execution, options: &RunOptions, header: bool) -> Result<()> {
+    let result = &execution.result;
+
+    if !options.output.is_human() {
+        if let Some(curl) = &execution.curl {
+            eprintln!("{}", curl);
+        }
+        return output::emit_result(result, options.output);
+    }
+
+    if header {
+        println!("\n🚀 Running: {}", result.name);
+    }
+    if let Some(curl) = &execution.curl {
+        println!("{}", curl);
+    }
+    for warning in &execution.warnings {
+        println!("⚠️  {}", warning);
+    }
+    if let Some(error) = &result.error {
+        eprintln!("❌ {}", error);
+    }
+    if let Some(response) = &execution.response {
+        display::display_response(response)?;
+        display::display_assertions(&result.assertions);
     }
 
     Ok(())
 }
 
-fn execute_request_config(config: &Config, request_config: &RequestConfig) -> Result<()> {
+pub fn convert(input_path: &str, output_path: &str) -> Result<()> {
+    let config = Config::load(input_path)?;
+    config.save(output_path)?;
+
+    println!(
+        "✅ Converted {} request(s) from {} to {}",
+        config.requests.len(),
+        input_path,
+        output_path
+    );
+
+    Ok(())
+}
+
+pub fn build_request(config: &Config, request_config: &RequestConfig) -> Result<Request> {
     let method = parse_method(&request_config.method)?;
     let url = config.substitute_variables(&request_config.url);
 
     let mut request = Request::new(method, url);
 
-    for (key, value) in &request_config.headers {
-        let substituted_value = config.substitute_variables(value);
-        request = request.header(key, substituted_value);
+    if let Some(graphql) = &request_config.graphql {
+        if request_config.body.is_some() {
+            return Err(Error::Config(format!(
+                "Request '{}' sets both body and graphql",
+                request_config.name
+            )));
+        }
+        request = build_graphql(config, request, graphql)?;
+    }
+
+    if let Some(path) = &request_config.body_file {
+        if request_config.body.is_some() || request_config.graphql.is_some() {
+            return Err(Error::Config(format!(
+                "Request '{}' sets body_file together with body or graphql",
+                request_config.name
+            )));
+        }
+        request = request.body_file(config.substitute_variables(path));
+        if request_config.json {
+            request = request.header("Content-Type", "application/json");
+        }
     }
 
     if let Some(body) = &request_config.body {
@@ -84,18 +695,172 @@ fn execute_request_config(config: &Config, request_config: &RequestConfig) -> Re
         if request_config.json {
             let json_value: serde_json::Value = serde_json::from_str(&substituted_body)?;
             request = request.json(&json_value)?;
+        } else if let Some(action) = &request_config.soap_action {
+            request = request.soap(&config.substitute_variables(action), substituted_body);
         } else {
             request = request.text(substituted_body);
         }
     }
 
-    let response = request.send()?;
-    display::display_response(&response)?;
+    if let Some(auth) = &request_config.basic_auth {
+        request = request.basic_auth(
+            config.substitute_variables(&auth.username),
+            config.substitute_variables(&auth.password),
+        );
+    }
+
+    for (key, value) in &request_config.headers {
+        let substituted_value = config.substitute_variables(value);
+        request = request.header(key, substituted_value);
+    }
+
+    for (name, value) in &request_config.form {
+        let value = config.substitute_variables(value);
+        request = match value.strip_prefix('@') {
+            Some(path) => request.form_file(name, path),
+            None => request.form_text(name, value),
+        };
+    }
+
+    if let Some(encoding) = &request_config.compress {
+        request = request.compress_body(encoding.parse()?);
+    }
 
+    if let Some(timeout) = request_config.timeout {
+        request = request.timeout(Duration::from_secs(timeout));
+    }
+
+    Ok(request.danger_accept_invalid_certs(request_config.insecure))
+}
+
+fn build_graphql(config: &Config, request: Request, graphql: &GraphqlConfig) -> Result<Request> {
+    let query = match (&graphql.query, &graphql.file) {
+        (Some(query), None) => query.clone(),
+        (None, Some(file)) => x_http::graphql::load_query(config.substitute_variables(file))?,
+        _ => {
+            return Err(Error::Config(
+                "graphql needs exactly one of query or file".to_string(),
+            ))
+        }
+    };
+    let variables = graphql
+        .variables
+        .as_ref()
+        .map(|variables| config.substitute_json(variables));
+
+    request.graphql(
+        &config.substitute_variables(&query),
+        variables.as_ref(),
+        graphql.operation_name.as_deref(),
+    )
+}
+
+fn execute_request_config(
+    config: &Config,
+    request_config: &RequestConfig,
+    options: &RunOptions,
+    session: &Session,
+    snapshot: &Snapshot,
+) -> Execution {
+    let mut execution = Execution::new(config, request_config);
+
+    if let Err(e) = send_request_config(
+        config,
+        request_config,
+        options,
+        session,
+        snapshot,
+        &mut execution,
+    ) {
+        execution.result.record_error(&e);
+    }
+
+    execution
+}
+
+fn send_request_config(
+    config: &Config,
+    request_config: &RequestConfig,
+    options: &RunOptions,
+    session: &Session,
+    snapshot: &Snapshot,
+    execution: &mut Execution,
+) -> Result<()> {
+    if !request_config.ws.is_empty() {
+        let started = Instant::now();
+        execution.result.assertions = ws::run_script(config, request_config)?;
+        execution.result.duration = started.elapsed();
+        return Ok(());
+    }
+
+    let method = parse_method(&request_config.method)?;
+    request_config.assertions.validate()?;
+    let request = build_request(config, request_config)?;
+
+    if options.print_curl {
+        execution.curl = Some(request.to_curl());
+    }
+
+    let result = &mut execution.result;
+    result.record_request(&request);
+    let response = session.send(request)?;
+    result.record_response(&response);
+
+    result.assertions = request_config.assertions.evaluate(&response, snapshot);
+    let (captured, outcomes) = request_config.capture.apply(&response);
+    result.assertions.extend(outcomes);
+    execution.captured = captured;
+    if let Some(spec) = &options.contract {
+        match check_contract(spec, method, &result.url, &response)? {
+            Some(outcome) => result.assertions.push(outcome),
+            None => execution.warnings.push(format!(
+                "No operation in the contract matches {} {}",
+                method.as_str(),
+                result.url
+            )),
+        }
+    }
+
+    if options.fail_on_http_error && response.is_error() {
+        result.assertions.push(AssertionOutcome::from_result(
+            "status is not an HTTP error",
+            response.check_success(),
+        ));
+        result.failure = ExitStatus::HttpError;
+    }
+
+    execution.response = Some(response);
     Ok(())
 }
 
-fn parse_method(method: &str) -> Result<Method> {
+fn check_contract(
+    spec: &OpenApi,
+    method: Method,
+    url: &str,
+    response: &Response,
+) -> Result<Option<AssertionOutcome>> {
+    let Some(op) = spec.match_operation(method, url) else {
+        return Ok(None);
+    };
+
+    let operation = format!("{} {}", op.method.as_str(), op.path);
+    let violations = spec.response_violations(&op, response)?;
+    let outcome = if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::ContractViolation {
+            operation: operation.clone(),
+            violations,
+        })
+    };
+
+    Ok(Some(AssertionOutcome::from_result(
+        format!("conforms to {}", operation),
+        outcome,
+    )))
+}
+
+pub fn parse_method(method: &str) -> Result<Method> {
     match method.to_uppercase().as_str() {
         "GET" => Ok(Method::Get),
         "POST" => Ok(Method::Post),
@@ -107,3 +872,36 @@ fn parse_method(method: &str) -> Result<Method> {
         _ => Err(Error::Config(format!("Invalid HTTP method: {}", method))),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graphql_variables_substitution() {
+        let config: Config = toml::from_str(
+            r#"
+[variables]
+name = 'say "hi"'
+
+[[requests]]
+name = "create"
+method = "POST"
+url = "https://api.example.com/graphql"
+
+[requests.graphql]
+query = "mutation($input: Input!) { create(input: $input) { id } }"
+variables = { input = { name = "{{name}}", tags = ["{{name}}", 1] } }
+"#,
+        )
+        .unwrap();
+
+        let request = build_request(&config, &config.requests[0]).unwrap();
+        let body: serde_json::Value =
+            serde_json::from_slice(request.body_bytes().unwrap()).unwrap();
+        assert_eq!(
+            body["variables"],
+            serde_json::json!({"input": {"name": "say \"hi\"", "tags": ["say \"hi\"", 1]}})
+        );
+    }
+}
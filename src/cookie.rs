@@ -0,0 +1,525 @@
+use crate::error::{Error, Result};
+use reqwest::cookie::CookieStore;
+use reqwest::header::HeaderValue;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use url::Url;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+impl SameSite {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SameSite::Strict => "Strict",
+            SameSite::Lax => "Lax",
+            SameSite::None => "None",
+        }
+    }
+}
+
+impl FromStr for SameSite {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "strict" => Ok(SameSite::Strict),
+            "lax" => Ok(SameSite::Lax),
+            "none" => Ok(SameSite::None),
+            _ => Err(Error::Assertion(format!(
+                "Invalid SameSite value '{}'",
+                value
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(
+        default,
+        with = "unix_seconds",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub expires: Option<SystemTime>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub secure: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub http_only: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub same_site: Option<SameSite>,
+}
+
+impl Cookie {
+    pub fn parse(set_cookie: &str) -> Option<Self> {
+        let mut parts = set_cookie.split(';');
+        let (name, value) = parts.next()?.split_once('=')?;
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+
+        let mut cookie = Cookie {
+            name: name.to_string(),
+            value: value.trim().trim_matches('"').to_string(),
+            domain: None,
+            path: None,
+            expires: None,
+            secure: false,
+            http_only: false,
+            same_site: None,
+        };
+        let mut max_age = None;
+
+        for attribute in parts {
+            let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "domain" if !value.is_empty() => {
+                    cookie.domain = Some(value.trim_start_matches('.').to_ascii_lowercase())
+                }
+                "path" if value.starts_with('/') => cookie.path = Some(value.to_string()),
+                "expires" => cookie.expires = parse_date(value).or(cookie.expires),
+                "max-age" => max_age = value.parse::<i64>().ok().or(max_age),
+                "secure" => cookie.secure = true,
+                "httponly" => cookie.http_only = true,
+                "samesite" => cookie.same_site = value.parse().ok(),
+                _ => {}
+            }
+        }
+
+        if let Some(seconds) = max_age {
+            cookie.expires = Some(match u64::try_from(seconds) {
+                Ok(seconds) if seconds > 0 => SystemTime::now() + Duration::from_secs(seconds),
+                _ => UNIX_EPOCH,
+            });
+        }
+
+        Some(cookie)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires
+            .is_some_and(|expires| expires <= SystemTime::now())
+    }
+
+    pub fn is_session(&self) -> bool {
+        self.expires.is_none()
+    }
+
+    pub fn expect_flags(&self, flags: &str) -> Result<()> {
+        let mut mismatches = Vec::new();
+        for flag in flags.split(';').map(str::trim).filter(|f| !f.is_empty()) {
+            let (key, expected) = flag.split_once('=').unwrap_or((flag, ""));
+            let expected = expected.trim();
+            let (matches, actual) = match key.trim().to_ascii_lowercase().as_str() {
+                "secure" => (self.secure, "not Secure".to_string()),
+                "httponly" => (self.http_only, "not HttpOnly".to_string()),
+                "samesite" => {
+                    let expected = expected.parse::<SameSite>()?;
+                    let actual = self.same_site.map_or("unset", |s| s.as_str());
+                    (
+                        self.same_site == Some(expected),
+                        format!("SameSite={}", actual),
+                    )
+                }
+                "path" => (
+                    self.path.as_deref() == Some(expected),
+                    format!("Path={}", self.path.as_deref().unwrap_or("unset")),
+                ),
+                "domain" => (
+                    self.domain.as_deref() == Some(expected.trim_start_matches('.')),
+                    format!("Domain={}", self.domain.as_deref().unwrap_or("unset")),
+                ),
+                "session" => (self.is_session(), "persistent".to_string()),
+                _ => return Err(Error::Assertion(format!("Unknown cookie flag '{}'", flag))),
+            };
+            if !matches {
+                mismatches.push(format!("expected {}, got {}", flag, actual));
+            }
+        }
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Assertion(format!(
+                "Cookie '{}': {}",
+                self.name,
+                mismatches.join("; ")
+            )))
+        }
+    }
+}
+
+impl fmt::Display for Cookie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.name, self.value)?;
+        if let Some(domain) = &self.domain {
+            write!(f, "; Domain={}", domain)?;
+        }
+        if let Some(path) = &self.path {
+            write!(f, "; Path={}", path)?;
+        }
+        if let Some(expires) = self.expires {
+            write!(f, "; Expires={}", httpdate::fmt_http_date(expires))?;
+        }
+        if self.secure {
+            write!(f, "; Secure")?;
+        }
+        if self.http_only {
+            write!(f, "; HttpOnly")?;
+        }
+        if let Some(same_site) = self.same_site {
+            write!(f, "; SameSite={}", same_site.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredCookie {
+    host: String,
+    #[serde(flatten)]
+    cookie: Cookie,
+}
+
+impl StoredCookie {
+    fn matches(&self, url: &Url) -> bool {
+        let host = url.host_str().unwrap_or_default().to_ascii_lowercase();
+        let domain_matches = match &self.cookie.domain {
+            Some(domain) => domain_matches(&host, domain),
+            None => host == self.host,
+        };
+        domain_matches
+            && path_matches(url.path(), self.path())
+            && (!self.cookie.secure || url.scheme() == "https")
+            && !self.cookie.is_expired()
+    }
+
+    fn path(&self) -> &str {
+        self.cookie.path.as_deref().unwrap_or("/")
+    }
+
+    fn same_slot(&self, other: &StoredCookie) -> bool {
+        self.cookie.name == other.cookie.name
+            && self.cookie.domain == other.cookie.domain
+            && self.path() == other.path()
+            && (self.cookie.domain.is_some() || self.host == other.host)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CookieJar {
+    cookies: Arc<Mutex<Vec<StoredCookie>>>,
+}
+
+impl CookieJar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut cookies: Vec<StoredCookie> = serde_json::from_str(&contents)?;
+        cookies.retain(|stored| !stored.cookie.is_expired());
+        Ok(Self {
+            cookies: Arc::new(Mutex::new(cookies)),
+        })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut cookies = self.lock();
+        cookies.retain(|stored| !stored.cookie.is_expired());
+        fs::write(path, serde_json::to_string_pretty(&*cookies)?)?;
+        Ok(())
+    }
+
+    pub fn insert(&self, url: &str, cookie: Cookie) -> Result<()> {
+        let url = Url::parse(url)?;
+        self.store(&url, cookie);
+        Ok(())
+    }
+
+    pub fn cookies(&self) -> Vec<Cookie> {
+        self.lock()
+            .iter()
+            .filter(|stored| !stored.cookie.is_expired())
+            .map(|stored| stored.cookie.clone())
+            .collect()
+    }
+
+    pub fn cookies_for(&self, url: &str) -> Result<Vec<Cookie>> {
+        let url = Url::parse(url)?;
+        Ok(self.matching(&url))
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn matching(&self, url: &Url) -> Vec<Cookie> {
+        let cookies = self.lock();
+        let mut matching: Vec<&StoredCookie> = cookies
+            .iter()
+            .filter(|stored| stored.matches(url))
+            .collect();
+        matching.sort_by_key(|stored| std::cmp::Reverse(stored.path().len()));
+        matching
+            .into_iter()
+            .map(|stored| stored.cookie.clone())
+            .collect()
+    }
+
+    fn store(&self, url: &Url, mut cookie: Cookie) {
+        let host = url.host_str().unwrap_or_default().to_ascii_lowercase();
+        if let Some(domain) = &cookie.domain {
+            if !domain_matches(&host, domain) {
+                return;
+            }
+        }
+        if cookie.path.is_none() {
+            cookie.path = Some(default_path(url.path()));
+        }
+
+        let stored = StoredCookie { host, cookie };
+        let mut cookies = self.lock();
+        cookies.retain(|existing| !existing.same_slot(&stored));
+        if !stored.cookie.is_expired() {
+            cookies.push(stored);
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<StoredCookie>> {
+        self.cookies.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl CookieStore for CookieJar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        for header in cookie_headers {
+            if let Some(cookie) = header.to_str().ok().and_then(Cookie::parse) {
+                self.store(url, cookie);
+            }
+        }
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        let header = self
+            .matching(url)
+            .iter()
+            .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+            .collect::<Vec<_>>()
+            .join("; ");
+        if header.is_empty() {
+            return None;
+        }
+        HeaderValue::from_str(&header).ok()
+    }
+}
+
+fn parse_date(value: &str) -> Option<SystemTime> {
+    httpdate::parse_http_date(value)
+        .or_else(|_| httpdate::parse_http_date(&value.replace('-', " ")))
+        .ok()
+}
+
+fn domain_matches(host: &str, domain: &str) -> bool {
+    host == domain
+        || host
+            .strip_suffix(domain)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+fn path_matches(request_path: &str, cookie_path: &str) -> bool {
+    request_path == cookie_path
+        || request_path
+            .strip_prefix(cookie_path)
+            .is_some_and(|rest| cookie_path.ends_with('/') || rest.starts_with('/'))
+}
+
+fn default_path(request_path: &str) -> String {
+    match request_path.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(index) => request_path[..index].to_string(),
+    }
+}
+
+mod unix_seconds {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    pub fn serialize<S: Serializer>(
+        time: &Option<SystemTime>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match time {
+            Some(time) => serializer.serialize_some(
+                &time
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+            ),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<SystemTime>, D::Error> {
+        let seconds = Option::<u64>::deserialize(deserializer)?;
+        Ok(seconds.map(|seconds| UNIX_EPOCH + Duration::from_secs(seconds)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_set_cookie() {
+        let cookie = Cookie::parse(
+            "sid=abc123; Domain=.Example.com; Path=/app; Expires=Wed, 21-Oct-2099 07:28:00 GMT; Secure; HttpOnly; SameSite=Lax",
+        )
+        .unwrap();
+        assert_eq!(cookie.name, "sid");
+        assert_eq!(cookie.value, "abc123");
+        assert_eq!(cookie.domain.as_deref(), Some("example.com"));
+        assert_eq!(cookie.path.as_deref(), Some("/app"));
+        assert!(cookie.secure && cookie.http_only);
+        assert_eq!(cookie.same_site, Some(SameSite::Lax));
+        assert!(!cookie.is_expired() && !cookie.is_session());
+        assert_eq!(
+            cookie.to_string(),
+            "sid=abc123; Domain=example.com; Path=/app; Expires=Wed, 21 Oct 2099 07:28:00 GMT; Secure; HttpOnly; SameSite=Lax"
+        );
+
+        cookie
+            .expect_flags("Secure; HttpOnly; SameSite=Lax; Path=/app")
+            .unwrap();
+        let error = cookie.expect_flags("SameSite=Strict; session").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Assertion failed: Cookie 'sid': expected SameSite=Strict, got SameSite=Lax; expected session, got persistent"
+        );
+        assert!(cookie.expect_flags("Partitioned").is_err());
+
+        assert!(Cookie::parse("gone=; Max-Age=0").unwrap().is_expired());
+        assert!(Cookie::parse("theme=dark").unwrap().is_session());
+        assert!(Cookie::parse("no-equals-sign").is_none());
+    }
+
+    #[test]
+    fn test_cookie_jar_matching_and_persistence() {
+        let jar = CookieJar::new();
+        let url = Url::parse("https://api.example.com/v1/login").unwrap();
+        let headers = [
+            HeaderValue::from_static("sid=1; Path=/; Secure; HttpOnly"),
+            HeaderValue::from_static("pref=x; Domain=example.com; Path=/v1"),
+            HeaderValue::from_static("local=y"),
+            HeaderValue::from_static("evil=z; Domain=other.com"),
+        ];
+        jar.set_cookies(&mut headers.iter(), &url);
+
+        let header = |url: &str| {
+            CookieStore::cookies(&jar, &Url::parse(url).unwrap())
+                .map(|value| value.to_str().unwrap().to_string())
+        };
+        assert_eq!(
+            header("https://api.example.com/v1/users").as_deref(),
+            Some("pref=x; local=y; sid=1")
+        );
+        assert_eq!(
+            header("http://www.example.com/v1").as_deref(),
+            Some("pref=x")
+        );
+        assert_eq!(
+            header("https://api.example.com/v10"),
+            Some("sid=1".to_string())
+        );
+        assert_eq!(header("https://other.com/"), None);
+
+        jar.set_cookies(
+            &mut [HeaderValue::from_static("sid=2; Path=/; Secure")].iter(),
+            &url,
+        );
+        jar.set_cookies(
+            &mut [HeaderValue::from_static("local=; Max-Age=0")].iter(),
+            &url,
+        );
+        assert_eq!(
+            header("https://api.example.com/v1/x").as_deref(),
+            Some("pref=x; sid=2")
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jar.json");
+        jar.save(&path).unwrap();
+        let loaded = CookieJar::load(&path).unwrap();
+        assert_eq!(
+            loaded.cookies_for("https://api.example.com/v1").unwrap(),
+            jar.cookies_for("https://api.example.com/v1").unwrap()
+        );
+        assert!(CookieJar::load(dir.path().join("missing.json"))
+            .unwrap()
+            .cookies()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_cookie_exchange() {
+        use crate::mock::{MockServer, Stub};
+        use crate::request::Request;
+        use crate::session::Session;
+
+        let server = MockServer::start().unwrap();
+        server.mock(
+            Stub::post("/login")
+                .response_header("Set-Cookie", "sid=abc; Path=/; HttpOnly; SameSite=Strict")
+                .response_header("Set-Cookie", "theme=dark; Max-Age=3600"),
+        );
+        server.mock(Stub::get("/me").header("Cookie", "sid=abc; theme=dark"));
+
+        let jar = CookieJar::new();
+        let session = Session::new().cookie_jar(jar.clone());
+        let response = session
+            .send(Request::post(server.url("/login")))
+            .unwrap()
+            .expect_cookie("sid", "abc")
+            .unwrap()
+            .expect_cookie_flags("sid", "HttpOnly; SameSite=Strict; Path=/")
+            .unwrap();
+        assert_eq!(response.cookies().len(), 2);
+        assert!(response.cookie("theme").unwrap().expires.is_some());
+        assert!(response.check_cookie("missing", "x").is_err());
+        assert!(response.expect_cookie_flags("sid", "Secure").is_err());
+
+        session
+            .send(Request::get(server.url("/me")))
+            .unwrap()
+            .expect_status(200)
+            .unwrap();
+        assert_eq!(jar.cookies().len(), 2);
+        assert!(Request::get(server.url("/me")).send().unwrap().is_error());
+    }
+}
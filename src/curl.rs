@@ -0,0 +1,344 @@
+use crate::config::RequestConfig;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use x_http::compression::ACCEPT_ENCODING;
+use x_http::error::{Error, Result};
+
+const SHORT_WITH_VALUE: &[&str] = &[
+    "-X", "-H", "-d", "-F", "-u", "-A", "-e", "-b", "-m", "-o", "-w",
+];
+
+pub fn parse(command: &str) -> Result<RequestConfig> {
+    let command = command.replace("\\\r\n", " ").replace("\\\n", " ");
+    let words = shell_words::split(&command)
+        .map_err(|e| Error::Config(format!("Failed to parse curl command: {}", e)))?;
+
+    let mut args: Vec<String> = words.into_iter().rev().collect();
+    if args.last().map(String::as_str) == Some("curl") {
+        args.pop();
+    }
+
+    let mut request = RequestConfig::default();
+    let mut method = None;
+    let mut data: Vec<String> = Vec::new();
+    let mut data_files: Vec<String> = Vec::new();
+    let mut get_with_data = false;
+    let mut head = false;
+
+    while let Some(arg) = args.pop() {
+        if let Some(flags) = expand_short_flags(&arg) {
+            args.extend(flags.into_iter().rev());
+            continue;
+        }
+
+        let (flag, inline_value) = split_flag(&arg);
+        let mut value = || -> Result<String> {
+            match &inline_value {
+                Some(value) => Ok(value.clone()),
+                None => args
+                    .pop()
+                    .ok_or_else(|| Error::Config(format!("Missing value for {}", flag))),
+            }
+        };
+
+        match flag.as_str() {
+            "-X" | "--request" => method = Some(value()?.to_uppercase()),
+            "-H" | "--header" => {
+                let header = value()?;
+                if let Some((key, val)) = header.split_once(':') {
+                    request
+                        .headers
+                        .insert(key.trim().to_string(), val.trim().to_string());
+                }
+            }
+            "--data-raw" => data.push(value()?),
+            "-d" | "--data" | "--data-binary" | "--data-ascii" => {
+                let value = value()?;
+                match value.strip_prefix('@') {
+                    Some(path) => data_files.push(path.to_string()),
+                    None => data.push(value),
+                }
+            }
+            "--data-urlencode" => {
+                let raw = value()?;
+                data.push(match raw.split_once('=') {
+                    Some((key, val)) => format!("{}={}", key, urlencode(val)),
+                    None => urlencode(&raw),
+                });
+            }
+            "--json" => {
+                let value = value()?;
+                match value.strip_prefix('@') {
+                    Some(path) => data_files.push(path.to_string()),
+                    None => data.push(value),
+                }
+                request.json = true;
+                request
+                    .headers
+                    .entry("Content-Type".to_string())
+                    .or_insert_with(|| "application/json".to_string());
+                request
+                    .headers
+                    .entry("Accept".to_string())
+                    .or_insert_with(|| "application/json".to_string());
+            }
+            "-F" | "--form" => {
+                let field = value()?;
+                let (name, val) = field
+                    .split_once('=')
+                    .ok_or_else(|| Error::Config(format!("Invalid form field: {}", field)))?;
+                request.form.insert(name.to_string(), val.to_string());
+            }
+            "-u" | "--user" => {
+                let credentials = value()?;
+                request.headers.insert(
+                    "Authorization".to_string(),
+                    format!("Basic {}", STANDARD.encode(credentials)),
+                );
+            }
+            "-A" | "--user-agent" => {
+                request.headers.insert("User-Agent".to_string(), value()?);
+            }
+            "-e" | "--referer" => {
+                request.headers.insert("Referer".to_string(), value()?);
+            }
+            "-b" | "--cookie" => {
+                request.headers.insert("Cookie".to_string(), value()?);
+            }
+            "-m" | "--max-time" => {
+                let seconds: f64 = value()?
+                    .parse()
+                    .map_err(|_| Error::Config("Invalid --max-time value".to_string()))?;
+                request.timeout = Some(seconds.ceil() as u64);
+            }
+            "--url" => request.url = value()?,
+            "-k" | "--insecure" => request.insecure = true,
+            "-G" | "--get" => get_with_data = true,
+            "-I" | "--head" => head = true,
+            "--compressed" => {
+                request
+                    .headers
+                    .entry("Accept-Encoding".to_string())
+                    .or_insert_with(|| ACCEPT_ENCODING.to_string());
+            }
+            "-L" | "--location" | "-s" | "--silent" | "-S" | "--show-error" | "-v"
+            | "--verbose" | "-i" | "--include" | "-f" | "--fail" => {}
+            "-o" | "--output" | "-w" | "--write-out" | "--connect-timeout" | "--retry" => {
+                value()?;
+            }
+            _ if !arg.starts_with('-') && request.url.is_empty() => request.url = arg.clone(),
+            _ => return Err(Error::Config(format!("Unsupported curl option: {}", arg))),
+        }
+    }
+
+    if request.url.is_empty() {
+        return Err(Error::Config("curl command has no URL".to_string()));
+    }
+
+    if let Some(path) = data_files.first() {
+        if data_files.len() > 1 || !data.is_empty() || get_with_data {
+            return Err(Error::Config(
+                "curl data read from a file cannot be combined with other data or -G".to_string(),
+            ));
+        }
+        if path == "-" {
+            return Err(Error::Config(
+                "curl data read from stdin (@-) cannot be imported".to_string(),
+            ));
+        }
+        request.body_file = Some(path.clone());
+    }
+
+    if get_with_data && !data.is_empty() {
+        let separator = if request.url.contains('?') { '&' } else { '?' };
+        request.url = format!("{}{}{}", request.url, separator, data.join("&"));
+        data.clear();
+    }
+
+    if !data.is_empty() || request.body_file.is_some() {
+        if !request.json {
+            request.json = request.headers.iter().any(|(key, value)| {
+                key.eq_ignore_ascii_case("content-type") && value.contains("application/json")
+            });
+        }
+        if !request.json
+            && !request
+                .headers
+                .keys()
+                .any(|key| key.eq_ignore_ascii_case("content-type"))
+        {
+            request.headers.insert(
+                "Content-Type".to_string(),
+                "application/x-www-form-urlencoded".to_string(),
+            );
+        }
+        if !data.is_empty() {
+            request.body = Some(data.join("&"));
+        }
+    }
+
+    let has_payload =
+        request.body.is_some() || request.body_file.is_some() || !request.form.is_empty();
+    request.method = method.unwrap_or_else(|| {
+        if head {
+            "HEAD".to_string()
+        } else if has_payload {
+            "POST".to_string()
+        } else {
+            "GET".to_string()
+        }
+    });
+    request.name = default_name(&request.method, &request.url);
+
+    Ok(request)
+}
+
+fn split_flag(arg: &str) -> (String, Option<String>) {
+    if let Some((flag, value)) = arg.split_once('=').filter(|_| arg.starts_with("--")) {
+        return (flag.to_string(), Some(value.to_string()));
+    }
+
+    if arg.len() > 2 && !arg.starts_with("--") && SHORT_WITH_VALUE.contains(&&arg[..2]) {
+        return (arg[..2].to_string(), Some(arg[2..].to_string()));
+    }
+
+    (arg.to_string(), None)
+}
+
+fn expand_short_flags(arg: &str) -> Option<Vec<String>> {
+    let cluster = arg.strip_prefix('-').filter(|rest| {
+        rest.len() > 1
+            && rest.is_ascii()
+            && !rest.starts_with('-')
+            && !SHORT_WITH_VALUE.contains(&&arg[..2])
+    })?;
+
+    let mut flags = Vec::new();
+    for (position, c) in cluster.char_indices() {
+        let flag = format!("-{}", c);
+        let takes_value = SHORT_WITH_VALUE.contains(&flag.as_str());
+        flags.push(flag);
+        if takes_value {
+            let value = &cluster[position + 1..];
+            if !value.is_empty() {
+                flags.push(value.to_string());
+            }
+            break;
+        }
+    }
+    Some(flags)
+}
+
+fn urlencode(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
+pub fn default_name(method: &str, url: &str) -> String {
+    let path = url::Url::parse(url)
+        .map(|u| u.path().to_string())
+        .unwrap_or_default();
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    if segments.is_empty() {
+        method.to_lowercase()
+    } else {
+        format!("{}-{}", method.to_lowercase(), segments.join("-"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_curl() {
+        let request = parse(
+            r#"curl -X PUT 'https://api.example.com/users/1' \
+  -H 'Content-Type: application/json' \
+  -H "Authorization: Bearer abc" \
+  --data-raw '{"name": "John"}' --compressed -k"#,
+        )
+        .unwrap();
+
+        assert_eq!(request.name, "put-users-1");
+        assert_eq!(request.method, "PUT");
+        assert_eq!(request.url, "https://api.example.com/users/1");
+        assert_eq!(request.headers["Authorization"], "Bearer abc");
+        assert_eq!(request.body.as_deref(), Some(r#"{"name": "John"}"#));
+        assert!(request.json);
+        assert!(request.insecure);
+        assert_eq!(request.headers["Accept-Encoding"], ACCEPT_ENCODING);
+    }
+
+    #[test]
+    fn test_parse_curl_combined_short_flags() {
+        let request = parse("curl -fsSL https://example.com/install.sh").unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.url, "https://example.com/install.sh");
+
+        let request =
+            parse("curl -sSXPOST https://example.com/users -sd name=John -ko out.json").unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.body.as_deref(), Some("name=John"));
+        assert!(request.insecure);
+
+        let request = parse("curl -sSH 'Accept: text/plain' https://example.com").unwrap();
+        assert_eq!(request.headers["Accept"], "text/plain");
+
+        assert!(parse("curl -sZ https://example.com").is_err());
+    }
+
+    #[test]
+    fn test_parse_curl_defaults() {
+        let post =
+            parse("curl https://example.com/login -d user=john -d pass=x -u me:secret").unwrap();
+        assert_eq!(post.method, "POST");
+        assert_eq!(post.body.as_deref(), Some("user=john&pass=x"));
+        assert_eq!(
+            post.headers["Content-Type"],
+            "application/x-www-form-urlencoded"
+        );
+        assert_eq!(post.headers["Authorization"], "Basic bWU6c2VjcmV0");
+
+        let upload =
+            parse("curl -F name=John -F avatar=@me.png https://example.com/upload").unwrap();
+        assert_eq!(upload.method, "POST");
+        assert_eq!(upload.form["avatar"], "@me.png");
+
+        let search = parse("curl -G https://example.com/search -d q=rust").unwrap();
+        assert_eq!(search.method, "GET");
+        assert_eq!(search.url, "https://example.com/search?q=rust");
+
+        assert!(parse("curl --bogus https://example.com").is_err());
+    }
+
+    #[test]
+    fn test_parse_curl_data_files() {
+        let upload = parse(
+            "curl https://example.com/users -H 'Content-Type: application/json' -d @payload.json",
+        )
+        .unwrap();
+        assert_eq!(upload.method, "POST");
+        assert_eq!(upload.body_file.as_deref(), Some("payload.json"));
+        assert!(upload.body.is_none());
+        assert!(upload.json);
+
+        let binary = parse("curl -X PUT https://example.com/blob --data-binary @dump.bin").unwrap();
+        assert_eq!(binary.body_file.as_deref(), Some("dump.bin"));
+        assert_eq!(
+            binary.headers["Content-Type"],
+            "application/x-www-form-urlencoded"
+        );
+
+        let json = parse("curl https://example.com/users --json @user.json").unwrap();
+        assert_eq!(json.body_file.as_deref(), Some("user.json"));
+        assert!(json.json);
+
+        let raw = parse("curl https://example.com/tags --data-raw @home").unwrap();
+        assert_eq!(raw.body.as_deref(), Some("@home"));
+        assert!(raw.body_file.is_none());
+
+        assert!(parse("curl https://example.com -d @a.json -d extra=1").is_err());
+        assert!(parse("curl https://example.com -d @-").is_err());
+    }
+}
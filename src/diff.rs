@@ -0,0 +1,410 @@
+use crate::config::{build_request, Config, RequestConfig};
+use crate::display;
+use crate::exit_status::ExitStatus;
+use crate::output::OutputFormat;
+use crate::schedule::{Plan, Step};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::thread;
+use x_http::error::{Error, Result};
+use x_http::snapshot::redact;
+use x_http::Response;
+
+const VOLATILE_HEADERS: &[&str] = &[
+    "date",
+    "age",
+    "expires",
+    "etag",
+    "last-modified",
+    "set-cookie",
+    "content-length",
+    "x-request-id",
+];
+
+pub struct DiffOptions {
+    pub ignore_paths: Vec<String>,
+    pub ignore_headers: Vec<String>,
+    pub output: OutputFormat,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Difference {
+    pub location: String,
+    pub a: Option<String>,
+    pub b: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RequestDiff {
+    pub name: String,
+    pub method: String,
+    pub url_a: String,
+    pub url_b: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub differences: Vec<Difference>,
+    #[serde(skip)]
+    pub status: ExitStatus,
+}
+
+impl RequestDiff {
+    fn new(config_a: &Config, config_b: &Config, index: usize) -> Self {
+        let request_config = &config_a.requests[index];
+        Self {
+            name: request_config.name.clone(),
+            method: request_config.method.clone(),
+            url_a: config_a.substitute_variables(&request_config.url),
+            url_b: config_b.substitute_variables(&config_b.requests[index].url),
+            error: None,
+            differences: Vec::new(),
+            status: ExitStatus::Success,
+        }
+    }
+
+    pub fn diverged(&self) -> bool {
+        self.error.is_some() || !self.differences.is_empty()
+    }
+}
+
+pub fn run(
+    config_path: &str,
+    env_a: &str,
+    env_b: &str,
+    request_name: Option<&str>,
+    options: &DiffOptions,
+) -> Result<ExitStatus> {
+    let mut config_a = Config::load(config_path)?;
+    let mut config_b = Config::load(config_path)?;
+    config_a.use_environment(env_a)?;
+    config_b.use_environment(env_b)?;
+
+    let mut plan = Plan::new(&config_a.requests, request_name)?;
+    let mut diffs = Vec::new();
+    let mut captured = (HashMap::new(), HashMap::new());
+
+    while let Some(step) = plan.next() {
+        let diff = match step {
+            Step::Run(index) => {
+                if options.output.is_human() {
+                    println!("\n🔀 Comparing: {}", config_a.requests[index].name);
+                }
+                let diff = compare_request(&config_a, &config_b, &mut captured, index, options);
+                plan.complete(index, diff.error.is_none());
+                diff
+            }
+            Step::Skip { index, dependency } => {
+                let mut diff = RequestDiff::new(&config_a, &config_b, index);
+                diff.error = Some(format!(
+                    "Skipped because dependency '{}' failed",
+                    config_a.requests[dependency].name
+                ));
+                diff.status = ExitStatus::AssertionFailed;
+                diff
+            }
+        };
+        if options.output.is_human() {
+            display::display_request_diff(&diff, env_a, env_b);
+        }
+        diffs.push(diff);
+    }
+
+    match options.output {
+        OutputFormat::Human => display::display_diff_summary(&diffs),
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            println!("{}", serde_json::to_string_pretty(&diffs)?)
+        }
+        _ => {
+            return Err(Error::Config(
+                "diff supports human and json output".to_string(),
+            ))
+        }
+    }
+
+    Ok(diffs.iter().map(|d| d.status).max().unwrap_or_default())
+}
+
+fn compare_request(
+    config_a: &Config,
+    config_b: &Config,
+    captured: &mut (HashMap<String, String>, HashMap<String, String>),
+    index: usize,
+    options: &DiffOptions,
+) -> RequestDiff {
+    let (request_a, request_b) = (&config_a.requests[index], &config_b.requests[index]);
+    let scoped_a = config_a.with_captured(&captured.0);
+    let scoped_b = config_b.with_captured(&captured.1);
+    let mut diff = RequestDiff::new(config_a, config_b, index);
+    diff.url_a = scoped_a.substitute_variables(&request_a.url);
+    diff.url_b = scoped_b.substitute_variables(&request_b.url);
+
+    let (a, b) = thread::scope(|scope| {
+        let a = scope.spawn(|| send(&scoped_a, request_a));
+        let b = send(&scoped_b, request_b);
+        (a.join().unwrap_or_else(|_| panicked()), b)
+    });
+
+    match (a, b) {
+        (Ok(a), Ok(b)) => {
+            captured.0.extend(request_a.capture.apply(&a).0);
+            captured.1.extend(request_b.capture.apply(&b).0);
+            diff.differences = compare(&a, &b, options);
+            if !diff.differences.is_empty() {
+                diff.status = ExitStatus::AssertionFailed;
+            }
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            diff.error = Some(e.to_string());
+            diff.status = ExitStatus::from_error(&e);
+        }
+    }
+
+    diff
+}
+
+fn send(config: &Config, request_config: &RequestConfig) -> Result<Response> {
+    build_request(config, request_config)?.send()
+}
+
+fn panicked() -> Result<Response> {
+    Err(Error::Config("Request worker stopped".to_string()))
+}
+
+pub fn compare(a: &Response, b: &Response, options: &DiffOptions) -> Vec<Difference> {
+    let mut differences = Vec::new();
+
+    if a.status() != b.status() {
+        differences.push(Difference {
+            location: "status".to_string(),
+            a: Some(a.status().to_string()),
+            b: Some(b.status().to_string()),
+        });
+    }
+
+    let ignored: BTreeSet<String> = VOLATILE_HEADERS
+        .iter()
+        .map(|h| h.to_string())
+        .chain(options.ignore_headers.iter().map(|h| h.to_lowercase()))
+        .collect();
+    let names: BTreeSet<&str> = a
+        .headers()
+        .keys()
+        .chain(b.headers().keys())
+        .map(|name| name.as_str())
+        .filter(|name| !ignored.contains(*name))
+        .collect();
+    for name in names {
+        let value_a = header_value(a, name);
+        let value_b = header_value(b, name);
+        if value_a != value_b {
+            differences.push(Difference {
+                location: format!("header {}", name),
+                a: value_a,
+                b: value_b,
+            });
+        }
+    }
+
+    match (a.json_value(), b.json_value()) {
+        (Ok(mut json_a), Ok(mut json_b)) => {
+            for path in &options.ignore_paths {
+                redact(&mut json_a, path);
+                redact(&mut json_b, path);
+            }
+            diff_values("$", &json_a, &json_b, &mut differences);
+        }
+        _ if a.body_bytes() != b.body_bytes() => differences.push(Difference {
+            location: "body".to_string(),
+            a: Some(format!("{} bytes", a.body_bytes().len())),
+            b: Some(format!("{} bytes", b.body_bytes().len())),
+        }),
+        _ => {}
+    }
+
+    differences
+}
+
+fn header_value(response: &Response, name: &str) -> Option<String> {
+    let values: Vec<&str> = response
+        .headers()
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .collect();
+    (!values.is_empty()).then(|| values.join(", "))
+}
+
+fn diff_values(path: &str, a: &Value, b: &Value, differences: &mut Vec<Difference>) {
+    match (a, b) {
+        (Value::Object(map_a), Value::Object(map_b)) => {
+            let keys: BTreeSet<&String> = map_a.keys().chain(map_b.keys()).collect();
+            for key in keys {
+                let child = format!("{}.{}", path, key);
+                match (map_a.get(key), map_b.get(key)) {
+                    (Some(a), Some(b)) => diff_values(&child, a, b, differences),
+                    (a, b) => differences.push(Difference {
+                        location: child,
+                        a: a.map(Value::to_string),
+                        b: b.map(Value::to_string),
+                    }),
+                }
+            }
+        }
+        (Value::Array(items_a), Value::Array(items_b)) => {
+            for index in 0..items_a.len().max(items_b.len()) {
+                let child = format!("{}[{}]", path, index);
+                match (items_a.get(index), items_b.get(index)) {
+                    (Some(a), Some(b)) => diff_values(&child, a, b, differences),
+                    (a, b) => differences.push(Difference {
+                        location: child,
+                        a: a.map(Value::to_string),
+                        b: b.map(Value::to_string),
+                    }),
+                }
+            }
+        }
+        (a, b) if a != b => differences.push(Difference {
+            location: path.to_string(),
+            a: Some(a.to_string()),
+            b: Some(b.to_string()),
+        }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use x_http::{MockServer, Request, Stub};
+
+    fn fetch(server: &MockServer, path: &str) -> Response {
+        Request::get(server.url(path)).send().unwrap()
+    }
+
+    #[test]
+    fn test_compare_responses_with_ignore_rules() {
+        let server = MockServer::start().unwrap();
+        server.mock(
+            Stub::get("/a")
+                .response_header("X-Trace", "1")
+                .response_header("Cache-Control", "no-cache")
+                .response_json(&json!({
+                    "id": 1,
+                    "name": "John",
+                    "roles": ["admin", "user"],
+                    "updatedAt": "2024-01-01",
+                    "profile": {"city": "Paris"}
+                }))
+                .unwrap(),
+        );
+        server.mock(
+            Stub::get("/b")
+                .status(201)
+                .response_header("X-Trace", "2")
+                .response_json(&json!({
+                    "id": 1,
+                    "name": "Jane",
+                    "roles": ["admin"],
+                    "updatedAt": "2025-06-30",
+                    "profile": {"city": "Paris", "zip": "75001"}
+                }))
+                .unwrap(),
+        );
+        server.mock(Stub::get("/text").response_body("ok"));
+        server.mock(Stub::get("/other-text").response_body("okay"));
+
+        let options = DiffOptions {
+            ignore_paths: vec!["$.updatedAt".to_string()],
+            ignore_headers: vec!["X-Trace".to_string()],
+            output: OutputFormat::Human,
+        };
+        let a = fetch(&server, "/a");
+        let b = fetch(&server, "/b");
+
+        let differences = compare(&a, &b, &options);
+        let locations: Vec<&str> = differences.iter().map(|d| d.location.as_str()).collect();
+        assert_eq!(
+            locations,
+            [
+                "status",
+                "header cache-control",
+                "$.name",
+                "$.profile.zip",
+                "$.roles[1]"
+            ]
+        );
+        assert_eq!(differences[1].b, None);
+        assert_eq!(differences[2].a.as_deref(), Some("\"John\""));
+        assert_eq!(differences[2].b.as_deref(), Some("\"Jane\""));
+        assert_eq!(differences[3].a, None);
+        assert_eq!(differences[4].b, None);
+
+        assert!(compare(&a, &fetch(&server, "/a"), &options).is_empty());
+
+        let text = compare(
+            &fetch(&server, "/text"),
+            &fetch(&server, "/other-text"),
+            &options,
+        );
+        assert_eq!(text[0].location, "body");
+    }
+
+    #[test]
+    fn test_compare_request_applies_captures() {
+        let server = MockServer::start().unwrap();
+        for (env, token) in [("a", "t1"), ("b", "t2")] {
+            server.mock(
+                Stub::get(format!("/{}/login", env))
+                    .response_header("Content-Type", "text/html")
+                    .response_body(format!(r#"<meta name="csrf-token" content="{}">"#, token)),
+            );
+            server.mock(
+                Stub::get(format!("/{}/me", env))
+                    .header("X-CSRF-Token", token)
+                    .response_json(&json!({"name": "John"}))
+                    .unwrap(),
+            );
+        }
+
+        let config = |env: &str| -> Config {
+            toml::from_str(&format!(
+                r#"
+[variables]
+BASE_URL = "{}/{}"
+
+[[requests]]
+name = "login"
+method = "GET"
+url = "{{{{BASE_URL}}}}/login"
+[requests.capture]
+csrf = "token"
+
+[[requests]]
+name = "me"
+method = "GET"
+url = "{{{{BASE_URL}}}}/me"
+headers = {{ "X-CSRF-Token" = "{{{{token}}}}" }}
+"#,
+                server.uri(),
+                env
+            ))
+            .unwrap()
+        };
+        let (config_a, config_b) = (config("a"), config("b"));
+        let options = DiffOptions {
+            ignore_paths: Vec::new(),
+            ignore_headers: Vec::new(),
+            output: OutputFormat::Json,
+        };
+
+        let mut captured = (HashMap::new(), HashMap::new());
+        let login = compare_request(&config_a, &config_b, &mut captured, 0, &options);
+        assert_eq!(login.differences[0].location, "body");
+        assert_eq!(captured.0["token"], "t1");
+        assert_eq!(captured.1["token"], "t2");
+
+        let me = compare_request(&config_a, &config_b, &mut captured, 1, &options);
+        assert!(!me.diverged(), "{:?}", me);
+    }
+}
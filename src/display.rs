@@ -1,15 +1,32 @@
+use crate::bench::BenchReport;
+use crate::diff::RequestDiff;
+use crate::output::{AssertionOutcome, RequestResult, Summary};
 use colored::Colorize;
+use std::time::Duration;
 use syntect::easy::HighlightLines;
 use syntect::highlighting::ThemeSet;
 use syntect::parsing::SyntaxSet;
 use syntect::util::{as_24_bit_terminal_escaped, LinesWithEndings};
+use tabled::settings::Style;
+use tabled::{Table, Tabled};
 use x_http::error::Result;
-use x_http::Response;
+use x_http::{Event, Response};
+
+const HEX_DUMP_LIMIT: usize = 512;
 
 pub fn display_response(response: &Response) -> Result<()> {
     println!("{}", "━".repeat(80).bright_blue());
     println!("{} {}", "Status:".bold(), format_status(response.status()));
     println!("{} {:?}", "Duration:".bold(), response.duration());
+    if let Some(encoding) = response.content_encoding() {
+        println!(
+            "{} {} ({} → {} bytes)",
+            "Encoding:".bold(),
+            encoding,
+            response.encoded_size(),
+            response.decoded_size()
+        );
+    }
 
     println!("\n{}", "Headers:".bold().cyan());
     for (key, value) in response.headers() {
@@ -20,20 +37,41 @@ pub fn display_response(response: &Response) -> Result<()> {
         );
     }
 
-    if let Ok(text) = response.text() {
-        println!("\n{}", "Body:".bold().cyan());
+    let cookies = response.cookies();
+    if !cookies.is_empty() {
+        println!("\n{}", "Cookies:".bold().cyan());
+        for cookie in cookies {
+            let text = cookie.to_string();
+            let (name, rest) = text.split_at(cookie.name.len());
+            println!("  {}{}", name.green(), rest);
+        }
+    }
+
+    match response.text() {
+        Ok(text) if !text.contains('\0') => {
+            println!("\n{}", "Body:".bold().cyan());
 
-        if let Some(content_type) = response.header("content-type") {
-            if content_type.contains("application/json") {
-                display_json(&text)?;
+            if let Some(content_type) = response.header("content-type") {
+                if content_type.contains("application/json") {
+                    display_json(&text)?;
+                } else if content_type.contains("xml") {
+                    display_xml(&text);
+                } else {
+                    println!("{}", text);
+                }
             } else {
                 println!("{}", text);
             }
-        } else {
-            println!("{}", text);
         }
-    } else {
-        println!("\n{}", "Body: <binary data>".dimmed());
+        _ => {
+            let body = response.body_bytes();
+            println!(
+                "\n{} {}",
+                "Body:".bold().cyan(),
+                format!("{} bytes of binary data", body.len()).dimmed()
+            );
+            print!("{}", hex_dump(body, HEX_DUMP_LIMIT));
+        }
     }
 
     println!("{}", "━".repeat(80).bright_blue());
@@ -41,6 +79,171 @@ pub fn display_response(response: &Response) -> Result<()> {
     Ok(())
 }
 
+pub fn display_assertions(assertions: &[AssertionOutcome]) {
+    if assertions.is_empty() {
+        return;
+    }
+
+    println!("{}", "Assertions:".bold().cyan());
+    for assertion in assertions {
+        match &assertion.message {
+            None => println!("  {} {}", "✓".green(), assertion.description),
+            Some(message) => println!(
+                "  {} {}: {}",
+                "✗".red(),
+                assertion.description,
+                message.red()
+            ),
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct SummaryRow {
+    #[tabled(rename = "Request")]
+    name: String,
+    #[tabled(rename = "Method")]
+    method: String,
+    #[tabled(rename = "URL")]
+    url: String,
+    #[tabled(rename = "Status")]
+    status: String,
+    #[tabled(rename = "Duration")]
+    duration: String,
+    #[tabled(rename = "Result")]
+    result: String,
+}
+
+pub fn display_summary(results: &[RequestResult], elapsed: Duration) {
+    let rows = results.iter().map(|result| SummaryRow {
+        name: result.name.clone(),
+        method: result.method.clone(),
+        url: result.url.clone(),
+        status: result
+            .status
+            .map_or_else(|| "-".to_string(), |s| s.to_string()),
+        duration: format!("{:.0?}", result.duration),
+        result: if result.passed() { "PASS" } else { "FAIL" }.to_string(),
+    });
+
+    println!("\n{}", "Summary:".bold().cyan());
+    println!("{}", Table::new(rows).with(Style::rounded()));
+
+    let summary = Summary::from_results(results);
+    let line = format!(
+        "{} passed, {} failed, {} total in {:.0?} (request time {:.0?})",
+        summary.passed, summary.failed, summary.total, elapsed, summary.duration
+    );
+    if summary.failed == 0 {
+        println!("{}", line.green().bold());
+    } else {
+        println!("{}", line.red().bold());
+        for result in results.iter().filter(|r| !r.passed()) {
+            for failure in result.failures() {
+                println!("  {} {}: {}", "✗".red(), result.name, failure);
+            }
+        }
+    }
+}
+
+pub fn display_bench(report: &BenchReport) {
+    println!("\n{}", "Summary:".bold().cyan());
+    println!("  Requests:    {}", report.requests);
+    println!("  Errors:      {}", report.errors);
+    println!("  Duration:    {:.2?}", report.elapsed);
+    println!("  Throughput:  {:.1} req/s", report.throughput);
+
+    let latency = &report.latency;
+    println!("\n{}", "Latency:".bold().cyan());
+    println!(
+        "  min {:.2?}  mean {:.2?}  p50 {:.2?}  p90 {:.2?}  p99 {:.2?}  max {:.2?}",
+        latency.min, latency.mean, latency.p50, latency.p90, latency.p99, latency.max
+    );
+
+    if !report.status_codes.is_empty() {
+        println!("\n{}", "Status codes:".bold().cyan());
+        for (status, count) in &report.status_codes {
+            println!("  {}  {}", format_status(*status), count);
+        }
+    }
+
+    if !report.error_messages.is_empty() {
+        println!("\n{}", "Errors:".bold().cyan());
+        for (message, count) in &report.error_messages {
+            println!("  {} {} {}", "✗".red(), count, message);
+        }
+    }
+
+    let busiest = report.histogram.iter().map(|b| b.count).max().unwrap_or(0);
+    if busiest > 0 {
+        println!("\n{}", "Histogram:".bold().cyan());
+        for bucket in &report.histogram {
+            let bar = "█".repeat((bucket.count * 40).div_ceil(busiest));
+            println!(
+                "  {:>10} - {:<10} │{} {}",
+                format!("{:.2?}", bucket.from),
+                format!("{:.2?}", bucket.to),
+                bar.bright_blue(),
+                bucket.count
+            );
+        }
+    }
+}
+
+pub fn display_request_diff(diff: &RequestDiff, env_a: &str, env_b: &str) {
+    if let Some(error) = &diff.error {
+        println!("  {} {}", "✗".red(), error);
+        return;
+    }
+    if diff.differences.is_empty() {
+        println!("  {} identical", "✓".green());
+        return;
+    }
+
+    let missing = || "<missing>".dimmed().to_string();
+    for difference in &diff.differences {
+        println!("  {} {}", "≠".yellow(), difference.location.bold());
+        println!(
+            "      {}: {}",
+            env_a,
+            difference.a.clone().unwrap_or_else(missing).red()
+        );
+        println!(
+            "      {}: {}",
+            env_b,
+            difference.b.clone().unwrap_or_else(missing).green()
+        );
+    }
+}
+
+pub fn display_diff_summary(diffs: &[RequestDiff]) {
+    let diverged = diffs.iter().filter(|d| d.diverged()).count();
+    let line = format!("{} of {} request(s) differ", diverged, diffs.len());
+
+    println!("\n{}", "Summary:".bold().cyan());
+    if diverged == 0 {
+        println!("{}", line.green().bold());
+    } else {
+        println!("{}", line.red().bold());
+        for diff in diffs.iter().filter(|d| d.diverged()) {
+            let reason = match &diff.error {
+                Some(error) => error.clone(),
+                None => format!("{} difference(s)", diff.differences.len()),
+            };
+            println!("  {} {}: {}", "✗".red(), diff.name, reason);
+        }
+    }
+}
+
+pub fn display_event(event: &Event) -> Result<()> {
+    let mut line = format!("{} {}", "▶".bright_blue(), event.event.bold());
+    if let Some(id) = &event.id {
+        line.push_str(&format!(" {}", format!("#{}", id).dimmed()));
+    }
+    println!("{}", line);
+    display_json(&event.data)
+}
+
 fn format_status(status: u16) -> String {
     let status_str = status.to_string();
     if (200..300).contains(&status) {
@@ -54,11 +257,44 @@ fn format_status(status: u16) -> String {
     }
 }
 
+fn hex_dump(bytes: &[u8], limit: usize) -> String {
+    let mut output = String::new();
+    for (index, line) in bytes[..bytes.len().min(limit)].chunks(16).enumerate() {
+        let hex: Vec<String> = line
+            .chunks(2)
+            .map(|pair| pair.iter().map(|b| format!("{:02x}", b)).collect())
+            .collect();
+        let ascii: String = line
+            .iter()
+            .map(|&b| {
+                if b.is_ascii_graphic() || b == b' ' {
+                    b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        output.push_str(&format!(
+            "{}: {:<39}  {}\n",
+            format!("{:08x}", index * 16).dimmed(),
+            hex.join(" "),
+            ascii
+        ));
+    }
+    if bytes.len() > limit {
+        output.push_str(&format!(
+            "{}\n",
+            format!("… {} more bytes", bytes.len() - limit).dimmed()
+        ));
+    }
+    output
+}
+
 fn display_json(json_text: &str) -> Result<()> {
     if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(json_text) {
         let formatted = serde_json::to_string_pretty(&parsed)?;
 
-        if let Some(highlighted) = highlight_json(&formatted) {
+        if let Some(highlighted) = highlight(&formatted, "json") {
             println!("{}", highlighted);
         } else {
             println!("{}", formatted);
@@ -70,17 +306,25 @@ fn display_json(json_text: &str) -> Result<()> {
     Ok(())
 }
 
-fn highlight_json(json_text: &str) -> Option<String> {
+fn display_xml(xml_text: &str) {
+    let formatted = x_http::xml::pretty_print(xml_text).unwrap_or_else(|_| xml_text.to_string());
+    match highlight(&formatted, "xml") {
+        Some(highlighted) => println!("{}", highlighted),
+        None => println!("{}", formatted),
+    }
+}
+
+fn highlight(text: &str, extension: &str) -> Option<String> {
     let ps = SyntaxSet::load_defaults_newlines();
     let ts = ThemeSet::load_defaults();
 
-    let syntax = ps.find_syntax_by_extension("json")?;
+    let syntax = ps.find_syntax_by_extension(extension)?;
     let theme = &ts.themes["base16-ocean.dark"];
 
     let mut highlighter = HighlightLines::new(syntax, theme);
     let mut output = String::new();
 
-    for line in LinesWithEndings::from(json_text) {
+    for line in LinesWithEndings::from(text) {
         let ranges = highlighter.highlight_line(line, &ps).ok()?;
         let escaped = as_24_bit_terminal_escaped(&ranges[..], false);
         output.push_str(&escaped);
@@ -88,3 +332,36 @@ fn highlight_json(json_text: &str) -> Option<String> {
 
     Some(output)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strip_ansi(text: &str) -> String {
+        let mut plain = String::new();
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                chars.by_ref().find(|c| c.is_ascii_alphabetic());
+            } else {
+                plain.push(c);
+            }
+        }
+        plain
+    }
+
+    #[test]
+    fn test_hex_dump() {
+        let bytes: Vec<u8> = b"Hello, world!\n\x00\x01\xff".to_vec();
+
+        assert_eq!(
+            strip_ansi(&hex_dump(&bytes, 512)),
+            "00000000: 4865 6c6c 6f2c 2077 6f72 6c64 210a 0001  Hello, world!...\n\
+             00000010: ff                                       .\n"
+        );
+        assert_eq!(
+            strip_ansi(&hex_dump(&bytes, 4)),
+            "00000000: 4865 6c6c                                Hell\n… 13 more bytes\n"
+        );
+    }
+}
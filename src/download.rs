@@ -0,0 +1,231 @@
+use indicatif::{ProgressBar, ProgressStyle};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read};
+use x_http::compression;
+use x_http::error::{Error, Result};
+use x_http::Request;
+
+pub const RANGE_NOT_SATISFIABLE: u16 = 416;
+const PARTIAL_CONTENT: u16 = 206;
+
+pub fn download(request: Request, path: &str, resume: bool) -> Result<u16> {
+    let existing = if resume {
+        fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+    } else {
+        0
+    };
+
+    let mut offset = 0;
+    let response = if existing > 0 {
+        let ranged = request
+            .clone()
+            .header("Range", format!("bytes={}-", existing))
+            .send_streaming()?;
+        let content_range = ranged.header("content-range").unwrap_or_default();
+        match ranged.status() {
+            RANGE_NOT_SATISFIABLE => {
+                if let Some(total) = range_total(content_range).filter(|&t| t != existing) {
+                    return Err(Error::Config(format!(
+                        "{} has {} bytes but the server has {}; download it again without --resume",
+                        path, existing, total
+                    )));
+                }
+                println!("✅ {} is already complete ({} bytes)", path, existing);
+                return Ok(RANGE_NOT_SATISFIABLE);
+            }
+            PARTIAL_CONTENT if range_start(content_range) == Some(existing) => {
+                offset = existing;
+                ranged
+            }
+            PARTIAL_CONTENT => {
+                println!(
+                    "⚠️  Server returned range '{}' instead of bytes {}-, downloading {} again",
+                    content_range, existing, path
+                );
+                request.send_streaming()?
+            }
+            _ => ranged,
+        }
+    } else {
+        request.send_streaming()?
+    };
+    let status = response.status();
+
+    if !response.is_success() {
+        return Ok(status);
+    }
+
+    let encodings = response
+        .header("content-encoding")
+        .and_then(compression::content_encodings)
+        .unwrap_or_default();
+    if offset > 0 && !encodings.is_empty() {
+        return Err(Error::Config(format!(
+            "The server compressed the rest of {}; resume it without --compressed",
+            path
+        )));
+    }
+
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(offset > 0)
+        .truncate(offset == 0)
+        .open(path)?;
+
+    let progress = match response.content_length().filter(|_| encodings.is_empty()) {
+        Some(length) => ProgressBar::new(offset + length).with_style(
+            ProgressStyle::with_template(
+                "{bar:40.cyan/blue} {bytes}/{total_bytes} {bytes_per_sec} eta {eta}",
+            )
+            .unwrap_or_else(|_| ProgressStyle::default_bar()),
+        ),
+        None => ProgressBar::new_spinner().with_style(
+            ProgressStyle::with_template("{spinner} {bytes} {bytes_per_sec}")
+                .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+        ),
+    };
+    progress.set_position(offset);
+
+    let mut writer = progress.wrap_write(file);
+    let mut reader: Box<dyn Read> = Box::new(response);
+    for encoding in encodings.iter().rev() {
+        reader = encoding.decoder(reader)?;
+    }
+    let written = io::copy(&mut reader, &mut writer)?;
+    progress.finish_and_clear();
+
+    if offset > 0 {
+        println!(
+            "📥 Resumed at {} bytes, saved {} more to {}",
+            offset, written, path
+        );
+    } else {
+        println!("📥 Saved {} bytes to {}", written, path);
+    }
+
+    Ok(status)
+}
+
+fn range_start(content_range: &str) -> Option<u64> {
+    let (start, _) = content_range.strip_prefix("bytes ")?.split_once('-')?;
+    start.trim().parse().ok()
+}
+
+fn range_total(content_range: &str) -> Option<u64> {
+    let (_, total) = content_range.rsplit_once('/')?;
+    total.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use x_http::compression::ACCEPT_ENCODING;
+    use x_http::{Encoding, MockServer, Stub};
+
+    #[test]
+    fn test_download_with_resume() {
+        let server = MockServer::start().unwrap();
+        server.mock(
+            Stub::get("/file")
+                .header("Range", "bytes=5-")
+                .status(206)
+                .response_header("Content-Range", "bytes 5-10/11")
+                .response_body("world"),
+        );
+        server.mock(Stub::get("/file").response_body("hello world"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file.txt");
+        let path = path.to_str().unwrap();
+
+        fs::write(path, "stale content").unwrap();
+        assert_eq!(
+            download(Request::get(server.url("/file")), path, false).unwrap(),
+            200
+        );
+        assert_eq!(fs::read_to_string(path).unwrap(), "hello world");
+
+        fs::write(path, "hello").unwrap();
+        assert_eq!(
+            download(Request::get(server.url("/file")), path, true).unwrap(),
+            206
+        );
+        assert_eq!(fs::read_to_string(path).unwrap(), "helloworld");
+
+        assert_eq!(
+            download(Request::get(server.url("/missing")), path, false).unwrap(),
+            404
+        );
+        assert_eq!(fs::read_to_string(path).unwrap(), "helloworld");
+    }
+
+    #[test]
+    fn test_resume_validates_ranges() {
+        let server = MockServer::start().unwrap();
+        server.mock(
+            Stub::get("/shifted")
+                .header("Range", "bytes=5-")
+                .status(206)
+                .response_header("Content-Range", "bytes 0-10/11")
+                .response_body("hello world"),
+        );
+        server.mock(Stub::get("/shifted").response_body("hello world"));
+        server.mock(
+            Stub::get("/done")
+                .header("Range", "bytes=5-")
+                .status(416)
+                .response_header("Content-Range", "bytes */5"),
+        );
+        server.mock(
+            Stub::get("/shorter")
+                .header("Range", "bytes=5-")
+                .status(416)
+                .response_header("Content-Range", "bytes */3"),
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file.txt");
+        let path = path.to_str().unwrap();
+
+        fs::write(path, "hello").unwrap();
+        assert_eq!(
+            download(Request::get(server.url("/shifted")), path, true).unwrap(),
+            200
+        );
+        assert_eq!(fs::read_to_string(path).unwrap(), "hello world");
+
+        fs::write(path, "hello").unwrap();
+        assert_eq!(
+            download(Request::get(server.url("/done")), path, true).unwrap(),
+            416
+        );
+        assert_eq!(fs::read_to_string(path).unwrap(), "hello");
+
+        assert!(matches!(
+            download(Request::get(server.url("/shorter")), path, true),
+            Err(Error::Config(_))
+        ));
+        assert_eq!(fs::read_to_string(path).unwrap(), "hello");
+    }
+
+    #[test]
+    fn test_download_decodes_compressed_bodies() {
+        let server = MockServer::start().unwrap();
+        let gzipped = Encoding::Gzip.encode(b"hello world").unwrap();
+        server.mock(
+            Stub::get("/file")
+                .header("Accept-Encoding", ACCEPT_ENCODING)
+                .response_header("Content-Encoding", "gzip")
+                .response_body(gzipped),
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file.txt");
+        let path = path.to_str().unwrap();
+
+        let request = Request::get(server.url("/file")).accept_compressed();
+        assert_eq!(download(request, path, false).unwrap(), 200);
+        assert_eq!(fs::read_to_string(path).unwrap(), "hello world");
+    }
+}
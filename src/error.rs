@@ -29,7 +29,7 @@ pub enum Error {
     #[error("Expected JSON response, got content-type: {0}")]
     NotJson(String),
 
-    #[error("JSON path '{path}' not found")]
+    #[error("Path '{path}' not found")]
     PathNotFound { path: String },
 
     #[error("Field '{field}' expected value {expected}, got {actual}")]
@@ -48,6 +48,45 @@ pub enum Error {
     #[error("TOML parsing error: {0}")]
     Toml(#[from] toml::de::Error),
 
+    #[error("OpenAPI error: {0}")]
+    OpenApi(String),
+
+    #[error("Response violates contract for '{operation}':\n  {}", .violations.join("\n  "))]
+    ContractViolation {
+        operation: String,
+        violations: Vec<String>,
+    },
+
+    #[error("Snapshot '{name}' does not match:\n{diff}")]
+    SnapshotMismatch { name: String, diff: String },
+
+    #[error("GraphQL response contains errors:\n  {}", .0.join("\n  "))]
+    GraphqlErrors(Vec<String>),
+
+    #[error("WebSocket error: {0}")]
+    WebSocket(String),
+
+    #[error("Response body exceeds the {limit} byte limit")]
+    BodyTooLarge { limit: u64 },
+
+    #[error("Event stream error: {0}")]
+    Sse(String),
+
+    #[error("HTTP {status}: {message}")]
+    HttpStatus { status: u16, message: String },
+
+    #[error("Compression error: {0}")]
+    Compression(String),
+
+    #[error("XML error: {0}")]
+    Xml(String),
+
+    #[error("HTML error: {0}")]
+    Html(String),
+
+    #[error("Cassette error: {0}")]
+    Cassette(String),
+
     #[error("Interactive prompt error: {0}")]
     Interactive(String),
 }
@@ -0,0 +1,94 @@
+use std::process::ExitCode;
+use x_http::Error;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ExitStatus {
+    #[default]
+    Success,
+    AssertionFailed,
+    HttpError,
+    NetworkError,
+    ConfigError,
+}
+
+impl ExitStatus {
+    pub fn code(self) -> u8 {
+        match self {
+            ExitStatus::Success => 0,
+            ExitStatus::AssertionFailed => 1,
+            ExitStatus::HttpError => 3,
+            ExitStatus::NetworkError => 4,
+            ExitStatus::ConfigError => 5,
+        }
+    }
+
+    pub fn from_error(error: &Error) -> Self {
+        match error {
+            Error::Request(_)
+            | Error::WebSocket(_)
+            | Error::Sse(_)
+            | Error::BodyTooLarge { .. }
+            | Error::Compression(_) => ExitStatus::NetworkError,
+            Error::Assertion(_)
+            | Error::StatusMismatch { .. }
+            | Error::HeaderMismatch { .. }
+            | Error::NotJson(_)
+            | Error::PathNotFound { .. }
+            | Error::FieldMismatch { .. }
+            | Error::ContractViolation { .. }
+            | Error::SnapshotMismatch { .. }
+            | Error::GraphqlErrors(_)
+            | Error::Xml(_)
+            | Error::Html(_) => ExitStatus::AssertionFailed,
+            Error::InvalidUrl(_)
+            | Error::Json(_)
+            | Error::Io(_)
+            | Error::Config(_)
+            | Error::Toml(_)
+            | Error::OpenApi(_)
+            | Error::Cassette(_)
+            | Error::Interactive(_) => ExitStatus::ConfigError,
+            Error::HttpStatus { .. } => ExitStatus::HttpError,
+        }
+    }
+}
+
+impl From<ExitStatus> for ExitCode {
+    fn from(status: ExitStatus) -> Self {
+        ExitCode::from(status.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_most_severe_status_wins() {
+        let statuses = [
+            ExitStatus::AssertionFailed,
+            ExitStatus::NetworkError,
+            ExitStatus::HttpError,
+        ];
+        assert_eq!(statuses.into_iter().max(), Some(ExitStatus::NetworkError));
+        assert_eq!(
+            ExitStatus::from_error(&Error::StatusMismatch {
+                expected: 200,
+                actual: 500
+            }),
+            ExitStatus::AssertionFailed
+        );
+        assert_eq!(
+            ExitStatus::from_error(&Error::Config("bad".to_string())).code(),
+            5
+        );
+        assert_eq!(
+            ExitStatus::from_error(&Error::HttpStatus {
+                status: 404,
+                message: "expected an event stream".to_string()
+            })
+            .code(),
+            3
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use crate::error::{Error, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Operation<'a> {
+    pub query: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variables: Option<&'a Value>,
+    #[serde(rename = "operationName", skip_serializing_if = "Option::is_none")]
+    pub operation_name: Option<&'a str>,
+}
+
+pub fn load_query(path: impl AsRef<Path>) -> Result<String> {
+    let path = path.as_ref();
+    fs::read_to_string(path).map_err(|e| {
+        Error::Config(format!(
+            "Failed to read GraphQL query {}: {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+pub fn error_messages(json: &Value) -> Vec<String> {
+    let Some(errors) = json.get("errors").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+
+    errors
+        .iter()
+        .map(|error| {
+            let message = error
+                .get("message")
+                .and_then(Value::as_str)
+                .map_or_else(|| error.to_string(), str::to_string);
+            match error.get("path").and_then(Value::as_array) {
+                Some(path) if !path.is_empty() => {
+                    let path: Vec<String> = path
+                        .iter()
+                        .map(|segment| match segment {
+                            Value::String(s) => s.clone(),
+                            other => other.to_string(),
+                        })
+                        .collect();
+                    format!("{} (at {})", message, path.join("."))
+                }
+                _ => message,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request::Request;
+    use crate::response::Response;
+    use reqwest::header::HeaderMap;
+    use reqwest::StatusCode;
+    use serde_json::json;
+
+    #[test]
+    fn test_graphql_request_body() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("user.graphql");
+        fs::write(&path, "query GetUser($id: ID!) { user(id: $id) { name } }").unwrap();
+
+        let request = Request::post("https://api.example.com/graphql")
+            .graphql_file(&path, Some(&json!({"id": 1})), Some("GetUser"))
+            .unwrap();
+        let body: Value = serde_json::from_slice(request.body_bytes().unwrap()).unwrap();
+        assert_eq!(
+            body,
+            json!({
+                "query": "query GetUser($id: ID!) { user(id: $id) { name } }",
+                "variables": {"id": 1},
+                "operationName": "GetUser"
+            })
+        );
+        assert_eq!(request.header_map()["content-type"], "application/json");
+
+        let request = Request::post("https://api.example.com/graphql")
+            .graphql("{ health }", None, None)
+            .unwrap();
+        assert_eq!(request.body_bytes().unwrap(), br#"{"query":"{ health }"}"#);
+    }
+
+    #[test]
+    fn test_graphql_assertions() {
+        let ok = Response::from_parts(
+            StatusCode::OK,
+            HeaderMap::new(),
+            json!({"data": {"user": {"name": "John", "roles": ["admin"]}}}).to_string(),
+        );
+        ok.expect_no_graphql_errors()
+            .unwrap()
+            .assert_data_field("user.name", "John")
+            .unwrap()
+            .assert_data_field("user.roles[0]", "admin")
+            .unwrap();
+
+        let failed = Response::from_parts(
+            StatusCode::OK,
+            HeaderMap::new(),
+            json!({
+                "data": {"user": null},
+                "errors": [{"message": "User not found", "path": ["user", 0]}]
+            })
+            .to_string(),
+        );
+        let error = failed.check_no_graphql_errors().unwrap_err();
+        assert!(matches!(error, Error::GraphqlErrors(_)));
+        assert!(error.to_string().contains("User not found (at user.0)"));
+
+        let error = failed.assert_data_field("user.name", "John").unwrap_err();
+        assert!(error.to_string().contains("User not found"));
+    }
+}
@@ -0,0 +1,476 @@
+use crate::error::Result;
+use crate::response::Response;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Har {
+    pub log: Log,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Log {
+    pub version: String,
+    pub creator: Creator,
+    #[serde(default)]
+    pub entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Creator {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Entry {
+    #[serde(default)]
+    pub started_date_time: String,
+    #[serde(default)]
+    pub time: f64,
+    pub request: HarRequest,
+    pub response: HarResponse,
+    #[serde(default)]
+    pub cache: serde_json::Value,
+    #[serde(default)]
+    pub timings: Timings,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default = "http_version")]
+    pub http_version: String,
+    #[serde(default)]
+    pub cookies: Vec<Cookie>,
+    #[serde(default)]
+    pub headers: Vec<NameValue>,
+    #[serde(default)]
+    pub query_string: Vec<NameValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<PostData>,
+    #[serde(default = "unknown_size")]
+    pub headers_size: i64,
+    #[serde(default = "unknown_size")]
+    pub body_size: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub status_text: String,
+    #[serde(default = "http_version")]
+    pub http_version: String,
+    #[serde(default)]
+    pub cookies: Vec<Cookie>,
+    #[serde(default)]
+    pub headers: Vec<NameValue>,
+    #[serde(default)]
+    pub content: Content,
+    #[serde(default, rename = "redirectURL")]
+    pub redirect_url: String,
+    #[serde(default = "unknown_size")]
+    pub headers_size: i64,
+    #[serde(default = "unknown_size")]
+    pub body_size: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NameValue {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_only: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secure: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostData {
+    #[serde(default)]
+    pub mime_type: String,
+    #[serde(default)]
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Content {
+    #[serde(default)]
+    pub size: i64,
+    #[serde(default)]
+    pub mime_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compression: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Timings {
+    #[serde(default = "unknown_timing")]
+    pub blocked: f64,
+    #[serde(default = "unknown_timing")]
+    pub dns: f64,
+    #[serde(default = "unknown_timing")]
+    pub connect: f64,
+    #[serde(default)]
+    pub send: f64,
+    #[serde(default)]
+    pub wait: f64,
+    #[serde(default)]
+    pub receive: f64,
+    #[serde(default = "unknown_timing")]
+    pub ssl: f64,
+}
+
+impl Default for Log {
+    fn default() -> Self {
+        Self {
+            version: "1.2".to_string(),
+            creator: Creator {
+                name: "x-http".to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl Default for Timings {
+    fn default() -> Self {
+        Self {
+            blocked: -1.0,
+            dns: -1.0,
+            connect: -1.0,
+            send: 0.0,
+            wait: 0.0,
+            receive: 0.0,
+            ssl: -1.0,
+        }
+    }
+}
+
+fn http_version() -> String {
+    "HTTP/1.1".to_string()
+}
+
+fn unknown_size() -> i64 {
+    -1
+}
+
+fn unknown_timing() -> f64 {
+    -1.0
+}
+
+impl Har {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(contents: &str) -> Result<Self> {
+        Ok(serde_json::from_str(contents)?)
+    }
+
+    pub fn add(&mut self, response: &Response) {
+        self.log.entries.extend(response.har_entries());
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+impl Response {
+    pub fn har_entries(&self) -> Vec<Entry> {
+        let request = self.request();
+        let started = format_timestamp(self.started_at());
+        let mut entries = Vec::new();
+
+        let mut cookie_header = request_cookies(&request.headers);
+        for redirect in self.redirects() {
+            entries.push(Entry {
+                started_date_time: started.clone(),
+                time: 0.0,
+                request: HarRequest {
+                    method: request.method.clone(),
+                    url: redirect.url.clone(),
+                    cookies: std::mem::take(&mut cookie_header),
+                    query_string: query_string(&redirect.url),
+                    ..HarRequest::default()
+                },
+                response: HarResponse {
+                    status: redirect.status,
+                    status_text: status_text(redirect.status),
+                    headers: vec![NameValue {
+                        name: "location".to_string(),
+                        value: redirect.location.clone(),
+                    }],
+                    redirect_url: redirect.location.clone(),
+                    ..HarResponse::default()
+                },
+                cache: serde_json::json!({}),
+                timings: Timings::default(),
+            });
+        }
+
+        let url = if self.url().is_empty() {
+            request.url.clone()
+        } else {
+            self.url().to_string()
+        };
+        let millis = self.duration().as_secs_f64() * 1000.0;
+        let body = self.body_bytes();
+        let (text, encoding) = match std::str::from_utf8(body) {
+            Ok(text) => (text.to_string(), None),
+            Err(_) => (STANDARD.encode(body), Some("base64".to_string())),
+        };
+
+        entries.push(Entry {
+            started_date_time: started,
+            time: millis,
+            request: HarRequest {
+                method: request.method.clone(),
+                url: url.clone(),
+                http_version: http_version(),
+                cookies: if self.redirects().is_empty() {
+                    request_cookies(&request.headers)
+                } else {
+                    Vec::new()
+                },
+                headers: name_values(&request.headers),
+                query_string: query_string(&url),
+                post_data: request.body.as_ref().map(|body| PostData {
+                    mime_type: header_value(&request.headers, "content-type"),
+                    text: String::from_utf8_lossy(body).into_owned(),
+                }),
+                headers_size: -1,
+                body_size: request.body.as_ref().map_or(0, |b| b.len() as i64),
+            },
+            response: HarResponse {
+                status: self.status(),
+                status_text: status_text(self.status()),
+                http_version: http_version(),
+                cookies: response_cookies(self.headers()),
+                headers: name_values(self.headers()),
+                content: Content {
+                    size: self.decoded_size() as i64,
+                    mime_type: header_value(self.headers(), "content-type"),
+                    text: (!body.is_empty()).then_some(text),
+                    encoding,
+                    compression: (self.decoded_size() != self.encoded_size())
+                        .then(|| self.decoded_size() as i64 - self.encoded_size() as i64),
+                },
+                redirect_url: self.header("location").unwrap_or_default().to_string(),
+                headers_size: -1,
+                body_size: self.encoded_size() as i64,
+            },
+            cache: serde_json::json!({}),
+            timings: Timings {
+                wait: millis,
+                ..Timings::default()
+            },
+        });
+
+        entries
+    }
+}
+
+fn name_values(headers: &HeaderMap) -> Vec<NameValue> {
+    headers
+        .iter()
+        .map(|(name, value)| NameValue {
+            name: name.as_str().to_string(),
+            value: String::from_utf8_lossy(value.as_bytes()).into_owned(),
+        })
+        .collect()
+}
+
+fn header_value(headers: &HeaderMap, name: &str) -> String {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn query_string(url: &str) -> Vec<NameValue> {
+    url::Url::parse(url)
+        .map(|url| {
+            url.query_pairs()
+                .map(|(name, value)| NameValue {
+                    name: name.into_owned(),
+                    value: value.into_owned(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn status_text(status: u16) -> String {
+    StatusCode::from_u16(status)
+        .ok()
+        .and_then(|s| s.canonical_reason())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn request_cookies(headers: &HeaderMap) -> Vec<Cookie> {
+    headers
+        .get_all("cookie")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .map(|(name, value)| Cookie {
+            name: name.to_string(),
+            value: value.to_string(),
+            ..Cookie::default()
+        })
+        .collect()
+}
+
+fn response_cookies(headers: &HeaderMap) -> Vec<Cookie> {
+    headers
+        .get_all("set-cookie")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .filter_map(crate::cookie::Cookie::parse)
+        .map(|cookie| Cookie {
+            name: cookie.name,
+            value: cookie.value,
+            path: cookie.path,
+            domain: cookie.domain,
+            expires: cookie.expires.map(format_timestamp),
+            http_only: cookie.http_only.then_some(true),
+            secure: cookie.secure.then_some(true),
+        })
+        .collect()
+}
+
+fn format_timestamp(time: SystemTime) -> String {
+    let elapsed = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = elapsed.as_secs();
+    let days = (seconds / 86_400) as i64;
+    let remainder = seconds % 86_400;
+
+    // Civil date from days since the epoch (Howard Hinnant's algorithm).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        remainder / 3600,
+        remainder % 3600 / 60,
+        remainder % 60,
+        elapsed.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockServer, Stub};
+    use crate::Request;
+    use std::time::Duration;
+
+    #[test]
+    fn test_format_timestamp() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+        assert_eq!(format_timestamp(time), "2023-11-14T22:13:20.123Z");
+        assert_eq!(format_timestamp(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+    }
+
+    #[test]
+    fn test_har_entries_with_redirects_and_cookies() {
+        let server = MockServer::start().unwrap();
+        server.mock(
+            Stub::get("/old")
+                .status(302)
+                .response_header("Location", "/new"),
+        );
+        server.mock(
+            Stub::get("/new")
+                .response_header("Set-Cookie", "session=abc; Path=/; HttpOnly")
+                .response_json(&serde_json::json!({"ok": true}))
+                .unwrap(),
+        );
+
+        let response = Request::get(server.url("/old"))
+            .query("q", "1")
+            .header("Cookie", "theme=dark")
+            .send()
+            .unwrap();
+
+        let mut har = Har::new();
+        har.add(&response);
+        let entries = &har.log.entries;
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].response.status, 302);
+        assert_eq!(entries[0].response.redirect_url, server.url("/new"));
+        assert_eq!(entries[0].request.cookies[0].value, "dark");
+        assert_eq!(entries[1].request.url, server.url("/new"));
+        assert_eq!(entries[1].response.cookies[0].name, "session");
+        assert_eq!(entries[1].response.cookies[0].http_only, Some(true));
+        assert_eq!(entries[1].response.content.mime_type, "application/json");
+        assert_eq!(
+            entries[1].response.content.text.as_deref(),
+            Some(r#"{"ok":true}"#)
+        );
+
+        let json = serde_json::to_value(&har).unwrap();
+        assert_eq!(json["log"]["version"], "1.2");
+        assert!(json["log"]["entries"][0]["response"]["redirectURL"].is_string());
+        assert!(json["log"]["entries"][1]["startedDateTime"]
+            .as_str()
+            .unwrap()
+            .ends_with('Z'));
+
+        let parsed = Har::parse(&serde_json::to_string(&har).unwrap()).unwrap();
+        assert_eq!(parsed.log.entries.len(), 2);
+    }
+}
@@ -0,0 +1,193 @@
+use crate::config::{AssertionConfig, Config, RequestConfig};
+use crate::curl::default_name;
+use std::collections::{HashMap, HashSet};
+use x_http::error::Result;
+use x_http::har::{Entry, Har};
+
+const SKIPPED_HEADERS: &[&str] = &[
+    "host",
+    "content-length",
+    "connection",
+    "accept-encoding",
+    "transfer-encoding",
+];
+
+#[derive(Debug, Default)]
+pub struct HarFilter {
+    pub hosts: Vec<String>,
+    pub mime_types: Vec<String>,
+    pub with_assertions: bool,
+}
+
+pub fn import(path: &str, filter: &HarFilter) -> Result<Config> {
+    let har = Har::from_file(path)?;
+    Ok(from_har(&har, filter))
+}
+
+pub fn from_har(har: &Har, filter: &HarFilter) -> Config {
+    let mut names = HashSet::new();
+    let requests = har
+        .log
+        .entries
+        .iter()
+        .filter(|entry| filter.matches(entry))
+        .map(|entry| convert_entry(entry, filter.with_assertions, &mut names))
+        .collect();
+
+    Config {
+        requests,
+        ..Config::default()
+    }
+}
+
+impl HarFilter {
+    fn matches(&self, entry: &Entry) -> bool {
+        let host = url::Url::parse(&entry.request.url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_lowercase))
+            .unwrap_or_default();
+        let host_matches = self.hosts.is_empty()
+            || self.hosts.iter().any(|wanted| {
+                let wanted = wanted.to_lowercase();
+                host == wanted || host.ends_with(&format!(".{}", wanted))
+            });
+
+        let mime = entry.response.content.mime_type.to_lowercase();
+        let mime_matches = self.mime_types.is_empty()
+            || self
+                .mime_types
+                .iter()
+                .any(|wanted| mime.contains(&wanted.to_lowercase()));
+
+        host_matches && mime_matches
+    }
+}
+
+fn convert_entry(
+    entry: &Entry,
+    with_assertions: bool,
+    names: &mut HashSet<String>,
+) -> RequestConfig {
+    let request = &entry.request;
+
+    let headers: HashMap<String, String> = request
+        .headers
+        .iter()
+        .filter(|header| {
+            !header.name.starts_with(':')
+                && !SKIPPED_HEADERS.contains(&header.name.to_lowercase().as_str())
+        })
+        .map(|header| (header.name.clone(), header.value.clone()))
+        .collect();
+
+    let body = request
+        .post_data
+        .as_ref()
+        .map(|data| data.text.clone())
+        .filter(|text| !text.is_empty());
+    let json = request
+        .post_data
+        .as_ref()
+        .is_some_and(|data| data.mime_type.contains("json"));
+
+    let assertions = AssertionConfig {
+        status: with_assertions.then_some(entry.response.status),
+        ..AssertionConfig::default()
+    };
+
+    RequestConfig {
+        name: unique_name(default_name(&request.method, &request.url), names),
+        method: request.method.to_uppercase(),
+        url: request.url.clone(),
+        headers,
+        body,
+        json,
+        assertions,
+        ..RequestConfig::default()
+    }
+}
+
+fn unique_name(name: String, names: &mut HashSet<String>) -> String {
+    let mut candidate = name.clone();
+    let mut counter = 2;
+    while !names.insert(candidate.clone()) {
+        candidate = format!("{}-{}", name, counter);
+        counter += 1;
+    }
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HAR: &str = r#"{
+  "log": {
+    "version": "1.2",
+    "creator": {"name": "Firefox", "version": "120"},
+    "entries": [
+      {
+        "startedDateTime": "2024-01-01T00:00:00.000Z",
+        "time": 12.5,
+        "request": {
+          "method": "GET",
+          "url": "https://api.example.com/users?page=1",
+          "headers": [
+            {"name": ":authority", "value": "api.example.com"},
+            {"name": "Accept", "value": "application/json"},
+            {"name": "Host", "value": "api.example.com"}
+          ]
+        },
+        "response": {"status": 200, "content": {"mimeType": "application/json; charset=utf-8"}}
+      },
+      {
+        "request": {
+          "method": "POST",
+          "url": "https://api.example.com/users",
+          "postData": {"mimeType": "application/json", "text": "{\"name\": \"John\"}"}
+        },
+        "response": {"status": 201, "content": {"mimeType": "application/json"}}
+      },
+      {
+        "request": {"method": "GET", "url": "https://cdn.example.net/logo.png"},
+        "response": {"status": 200, "content": {"mimeType": "image/png"}}
+      },
+      {
+        "request": {"method": "GET", "url": "https://api.example.com/users?page=2"},
+        "response": {"status": 200, "content": {"mimeType": "application/json"}}
+      }
+    ]
+  }
+}"#;
+
+    #[test]
+    fn test_import_har_with_filters() {
+        let har = Har::parse(HAR).unwrap();
+        let filter = HarFilter {
+            hosts: vec!["example.com".to_string()],
+            mime_types: vec!["json".to_string()],
+            with_assertions: true,
+        };
+        let config = from_har(&har, &filter);
+
+        assert_eq!(config.requests.len(), 3);
+
+        let list = &config.requests[0];
+        assert_eq!(list.name, "get-users");
+        assert_eq!(list.url, "https://api.example.com/users?page=1");
+        assert_eq!(list.headers.len(), 1);
+        assert_eq!(list.headers["Accept"], "application/json");
+        assert_eq!(list.assertions.status, Some(200));
+
+        let create = &config.requests[1];
+        assert_eq!(create.method, "POST");
+        assert!(create.json);
+        assert_eq!(create.body.as_deref(), Some(r#"{"name": "John"}"#));
+
+        assert_eq!(config.requests[2].name, "get-users-2");
+
+        let everything = from_har(&har, &HarFilter::default());
+        assert_eq!(everything.requests.len(), 4);
+        assert!(everything.requests[3].assertions.is_empty());
+    }
+}
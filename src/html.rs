@@ -0,0 +1,284 @@
+use crate::error::{Error, Result};
+use scraper::{ElementRef, Html, Selector};
+
+const FIELD_SELECTOR: &str = "input[name], select[name], textarea[name]";
+
+const CSRF_SELECTORS: &[(&str, &str)] = &[
+    ("meta[name='csrf-token']", "content"),
+    ("meta[name='csrf_token']", "content"),
+    ("meta[name='_csrf']", "content"),
+    ("input[name='csrf_token']", "value"),
+    ("input[name='csrfmiddlewaretoken']", "value"),
+    ("input[name='authenticity_token']", "value"),
+    ("input[name='__RequestVerificationToken']", "value"),
+    ("input[name='_token']", "value"),
+    ("input[name='_csrf']", "value"),
+    ("input[type='hidden'][name*='csrf' i]", "value"),
+    ("meta[name*='csrf' i]", "content"),
+];
+
+pub struct HtmlDocument {
+    html: Html,
+}
+
+impl HtmlDocument {
+    pub fn parse(html: &str) -> Self {
+        Self {
+            html: Html::parse_document(html),
+        }
+    }
+
+    pub fn count(&self, css: &str) -> Result<usize> {
+        Ok(self.html.select(&selector(css)?).count())
+    }
+
+    pub fn text(&self, css: &str) -> Result<Option<String>> {
+        Ok(self.first(css)?.map(normalized_text))
+    }
+
+    pub fn texts(&self, css: &str) -> Result<Vec<String>> {
+        Ok(self
+            .html
+            .select(&selector(css)?)
+            .map(normalized_text)
+            .collect())
+    }
+
+    pub fn attribute(&self, css: &str, attribute: &str) -> Result<Option<String>> {
+        Ok(self
+            .first(css)?
+            .and_then(|element| element.value().attr(attribute))
+            .map(str::to_string))
+    }
+
+    pub fn form_fields(&self, css: &str) -> Result<Vec<(String, String)>> {
+        let form = self.require(css)?;
+
+        let mut fields = Vec::new();
+        for field in form.select(&selector(FIELD_SELECTOR)?) {
+            let element = field.value();
+            if element.attr("disabled").is_some() {
+                continue;
+            }
+            let name = element.attr("name").unwrap_or_default().to_string();
+            let value = match element.name() {
+                "textarea" => field.text().collect(),
+                "select" => selected_option(field)?,
+                _ => {
+                    let kind = element.attr("type").unwrap_or("text").to_ascii_lowercase();
+                    match kind.as_str() {
+                        "submit" | "button" | "image" | "reset" | "file" => continue,
+                        "checkbox" | "radio" if element.attr("checked").is_none() => continue,
+                        "checkbox" | "radio" => element.attr("value").unwrap_or("on").to_string(),
+                        _ => element.attr("value").unwrap_or_default().to_string(),
+                    }
+                }
+            };
+            fields.push((name, value));
+        }
+        Ok(fields)
+    }
+
+    pub fn csrf_token(&self) -> Option<String> {
+        CSRF_SELECTORS.iter().find_map(|(css, attribute)| {
+            self.attribute(css, attribute)
+                .ok()
+                .flatten()
+                .filter(|token| !token.is_empty())
+        })
+    }
+
+    pub fn check_selector_exists(&self, css: &str) -> Result<()> {
+        self.require(css)?;
+        Ok(())
+    }
+
+    pub fn check_selector_text(&self, css: &str, expected: &str) -> Result<()> {
+        let actual = normalized_text(self.require(css)?);
+        if actual != expected {
+            return Err(Error::Assertion(format!(
+                "Expected '{}' to have text '{}', got '{}'",
+                css, expected, actual
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn check_selector_count(&self, css: &str, expected: usize) -> Result<()> {
+        let actual = self.count(css)?;
+        if actual != expected {
+            return Err(Error::Assertion(format!(
+                "Expected {} elements matching '{}', got {}",
+                expected, css, actual
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn check_attribute(&self, css: &str, attribute: &str, expected: &str) -> Result<()> {
+        match self.require(css)?.value().attr(attribute) {
+            Some(actual) if actual == expected => Ok(()),
+            Some(actual) => Err(Error::Assertion(format!(
+                "Expected '{}' attribute '{}' to be '{}', got '{}'",
+                css, attribute, expected, actual
+            ))),
+            None => Err(Error::Assertion(format!(
+                "Expected '{}' to have attribute '{}'",
+                css, attribute
+            ))),
+        }
+    }
+
+    fn require(&self, css: &str) -> Result<ElementRef<'_>> {
+        self.first(css)?.ok_or_else(|| Error::PathNotFound {
+            path: css.to_string(),
+        })
+    }
+
+    fn first(&self, css: &str) -> Result<Option<ElementRef<'_>>> {
+        Ok(self.html.select(&selector(css)?).next())
+    }
+}
+
+pub fn validate_selector(css: &str) -> Result<()> {
+    selector(css).map(drop)
+}
+
+fn selector(css: &str) -> Result<Selector> {
+    Selector::parse(css).map_err(|e| Error::Config(format!("Invalid selector '{}': {}", css, e)))
+}
+
+fn normalized_text(element: ElementRef) -> String {
+    element
+        .text()
+        .flat_map(str::split_whitespace)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn selected_option(select: ElementRef) -> Result<String> {
+    let option = select
+        .select(&selector("option[selected]")?)
+        .next()
+        .or_else(|| select.select(&selector("option").ok()?).next());
+    Ok(option
+        .map(|option| match option.value().attr("value") {
+            Some(value) => value.to_string(),
+            None => normalized_text(option),
+        })
+        .unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head><meta name="csrf-token" content="meta-token"></head>
+<body>
+  <h1>  Welcome,
+        John </h1>
+  <ul class="items"><li>One</li><li>Two</li><li>Three</li></ul>
+  <a class="next" href="/page/2">Next</a>
+  <form id="login" action="/login" method="post">
+    <input type="hidden" name="csrfmiddlewaretoken" value="form-token">
+    <input name="username" value="john">
+    <input type="password" name="password">
+    <input type="checkbox" name="remember" checked>
+    <input type="checkbox" name="newsletter" value="yes">
+    <input type="radio" name="plan" value="free">
+    <input type="radio" name="plan" value="pro" checked>
+    <select name="lang"><option value="en">English</option><option value="de" selected>Deutsch</option></select>
+    <textarea name="bio">Hello</textarea>
+    <input name="legacy" value="x" disabled>
+    <input type="submit" name="go" value="Log in">
+  </form>
+</body>
+</html>"#;
+
+    #[test]
+    fn test_selectors_and_forms() {
+        let document = HtmlDocument::parse(PAGE);
+
+        assert_eq!(document.text("h1").unwrap().unwrap(), "Welcome, John");
+        assert_eq!(document.count("ul.items li").unwrap(), 3);
+        assert_eq!(document.texts("li").unwrap(), vec!["One", "Two", "Three"]);
+        assert_eq!(
+            document.attribute("a.next", "href").unwrap().as_deref(),
+            Some("/page/2")
+        );
+        assert_eq!(document.text("table").unwrap(), None);
+        assert!(document.count("li[").is_err());
+
+        let fields = document.form_fields("form#login").unwrap();
+        let expected = [
+            ("csrfmiddlewaretoken", "form-token"),
+            ("username", "john"),
+            ("password", ""),
+            ("remember", "on"),
+            ("plan", "pro"),
+            ("lang", "de"),
+            ("bio", "Hello"),
+        ];
+        assert_eq!(
+            fields,
+            expected
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect::<Vec<_>>()
+        );
+        assert!(document.form_fields("form#missing").is_err());
+
+        assert_eq!(document.csrf_token().as_deref(), Some("meta-token"));
+        let form_only = HtmlDocument::parse(
+            r#"<form><input type="hidden" name="X-CSRF-Token" value="abc"></form>"#,
+        );
+        assert_eq!(form_only.csrf_token().as_deref(), Some("abc"));
+        assert_eq!(HtmlDocument::parse("<p>no token</p>").csrf_token(), None);
+    }
+
+    #[test]
+    fn test_html_assertions() {
+        use crate::mock::{MockServer, Stub};
+        use crate::request::Request;
+
+        let server = MockServer::start().unwrap();
+        server.mock(
+            Stub::get("/login")
+                .response_header("Content-Type", "text/html; charset=utf-8")
+                .response_body(PAGE),
+        );
+
+        let response = Request::get(server.url("/login"))
+            .send()
+            .unwrap()
+            .assert_selector_exists("form#login")
+            .unwrap()
+            .assert_selector_text("h1", "Welcome, John")
+            .unwrap()
+            .assert_selector_count("ul.items > li", 3)
+            .unwrap()
+            .assert_attribute("form#login", "action", "/login")
+            .unwrap();
+
+        let document = response.html().unwrap();
+        assert!(matches!(
+            document.check_selector_exists("table"),
+            Err(Error::PathNotFound { .. })
+        ));
+        assert!(matches!(
+            document.check_selector_count("li", 2),
+            Err(Error::Assertion(_))
+        ));
+        assert!(document
+            .check_attribute("a.next", "href", "/page/3")
+            .unwrap_err()
+            .to_string()
+            .contains("got '/page/2'"));
+        assert!(matches!(
+            response.assert_selector_text("li[", "x"),
+            Err(Error::Config(_))
+        ));
+    }
+}
@@ -0,0 +1,238 @@
+use crate::config::{Config, RequestConfig};
+use std::collections::HashMap;
+use x_http::error::{Error, Result};
+
+const METHODS: [&str; 7] = ["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS"];
+
+pub fn is_http_file(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    lower.ends_with(".http") || lower.ends_with(".rest")
+}
+
+pub fn parse(contents: &str) -> Result<Config> {
+    let mut variables = HashMap::new();
+    let mut requests = Vec::new();
+
+    for block in split_blocks(contents) {
+        if let Some(mut request) = parse_block(&block, &mut variables)? {
+            if request.name.is_empty() {
+                request.name = format!("request-{}", requests.len() + 1);
+            }
+            requests.push(request);
+        }
+    }
+
+    Ok(Config {
+        variables,
+        requests,
+        ..Config::default()
+    })
+}
+
+struct Block {
+    title: Option<String>,
+    lines: Vec<String>,
+}
+
+fn split_blocks(contents: &str) -> Vec<Block> {
+    let mut blocks = vec![Block {
+        title: None,
+        lines: Vec::new(),
+    }];
+
+    for line in contents.lines() {
+        if let Some(rest) = line.trim_start().strip_prefix("###") {
+            let title = rest.trim();
+            blocks.push(Block {
+                title: (!title.is_empty()).then(|| title.to_string()),
+                lines: Vec::new(),
+            });
+        } else if let Some(block) = blocks.last_mut() {
+            block.lines.push(line.to_string());
+        }
+    }
+
+    blocks
+}
+
+fn parse_block(
+    block: &Block,
+    variables: &mut HashMap<String, String>,
+) -> Result<Option<RequestConfig>> {
+    let mut name = block.title.clone();
+    let mut lines = block.lines.iter().map(String::as_str).peekable();
+
+    let request_line = loop {
+        let Some(line) = lines.next() else {
+            return Ok(None);
+        };
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() {
+            continue;
+        }
+        if let Some(comment) = strip_comment(trimmed) {
+            if let Some(value) = comment.trim().strip_prefix("@name") {
+                let value = value.trim_start();
+                name = Some(value.strip_prefix('=').unwrap_or(value).trim().to_string());
+            }
+            continue;
+        }
+        if let Some(definition) = trimmed.strip_prefix('@') {
+            let (key, value) = definition.split_once('=').ok_or_else(|| {
+                Error::Config(format!("Invalid variable definition: {}", trimmed))
+            })?;
+            let value = variables
+                .iter()
+                .fold(value.trim().to_string(), |value, (name, resolved)| {
+                    value.replace(&format!("{{{{{}}}}}", name), resolved)
+                });
+            variables.insert(key.trim().to_string(), value);
+            continue;
+        }
+
+        break trimmed;
+    };
+
+    let (method, mut url) = parse_request_line(request_line)?;
+
+    while let Some(line) = lines.peek() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('?') || trimmed.starts_with('&') {
+            url.push_str(trimmed);
+            lines.next();
+        } else {
+            break;
+        }
+    }
+
+    let mut headers = HashMap::new();
+    for line in lines.by_ref() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            break;
+        }
+        if strip_comment(trimmed).is_some() {
+            continue;
+        }
+        let (key, value) = trimmed
+            .split_once(':')
+            .ok_or_else(|| Error::Config(format!("Invalid header line: {}", trimmed)))?;
+        headers.insert(key.trim().to_string(), value.trim().to_string());
+    }
+
+    let body_lines: Vec<&str> = lines
+        .take_while(|line| !line.trim_start().starts_with("> {%"))
+        .collect();
+    let body = body_lines.join("\n").trim().to_string();
+
+    let json = headers.iter().any(|(key, value)| {
+        key.eq_ignore_ascii_case("content-type") && value.contains("application/json")
+    });
+
+    Ok(Some(RequestConfig {
+        name: name.unwrap_or_default(),
+        method,
+        url,
+        headers,
+        body: (!body.is_empty()).then_some(body),
+        json,
+        ..RequestConfig::default()
+    }))
+}
+
+fn parse_request_line(line: &str) -> Result<(String, String)> {
+    let mut parts = line.split_whitespace();
+    let first = parts
+        .next()
+        .ok_or_else(|| Error::Config("Missing request line".to_string()))?;
+
+    let (method, url) = if METHODS.contains(&first.to_uppercase().as_str()) {
+        let url = parts
+            .next()
+            .ok_or_else(|| Error::Config(format!("Missing URL in request line: {}", line)))?;
+        (first.to_uppercase(), url)
+    } else {
+        ("GET".to_string(), first)
+    };
+
+    Ok((method, url.to_string()))
+}
+
+fn strip_comment(line: &str) -> Option<&str> {
+    line.strip_prefix('#').or_else(|| line.strip_prefix("//"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_http_file() {
+        let contents = r#"
+@host = https://api.example.com
+@token = secret
+@users = {{host}}/v2/users
+@undefined = {{later}}
+
+### List users
+GET {{host}}/users
+    ?page=1
+Authorization: Bearer {{token}}
+
+###
+# @name create-user
+POST {{host}}/users HTTP/1.1
+Content-Type: application/json
+
+{
+  "name": "John"
+}
+
+###
+{{host}}/health
+
+###
+// @name = delete-user
+DELETE {{users}}/1
+"#;
+
+        let config = parse(contents).unwrap();
+
+        assert_eq!(config.variables["host"], "https://api.example.com");
+        assert_eq!(config.variables["token"], "secret");
+        assert_eq!(
+            config.variables["users"],
+            "https://api.example.com/v2/users"
+        );
+        assert_eq!(config.variables["undefined"], "{{later}}");
+        assert_eq!(config.requests.len(), 4);
+
+        let list = &config.requests[0];
+        assert_eq!(list.name, "List users");
+        assert_eq!(list.method, "GET");
+        assert_eq!(list.url, "{{host}}/users?page=1");
+        assert_eq!(list.headers["Authorization"], "Bearer {{token}}");
+        assert!(list.body.is_none());
+
+        let create = &config.requests[1];
+        assert_eq!(create.name, "create-user");
+        assert_eq!(create.method, "POST");
+        assert!(create.json);
+        assert_eq!(create.body.as_deref(), Some("{\n  \"name\": \"John\"\n}"));
+
+        let health = &config.requests[2];
+        assert_eq!(health.name, "request-3");
+        assert_eq!(health.method, "GET");
+        assert_eq!(health.url, "{{host}}/health");
+
+        assert_eq!(config.requests[3].name, "delete-user");
+    }
+
+    #[test]
+    fn test_is_http_file() {
+        assert!(is_http_file("requests.http"));
+        assert!(is_http_file("api.REST"));
+        assert!(!is_http_file("x-http.toml"));
+    }
+}
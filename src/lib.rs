@@ -1,8 +1,38 @@
 pub mod assertions;
+pub mod cassette;
+pub mod compression;
+pub mod cookie;
 pub mod error;
+pub mod graphql;
+pub mod har;
+pub mod html;
+pub mod mock;
+pub mod openapi;
 pub mod request;
 pub mod response;
+pub mod session;
+pub mod snapshot;
+pub mod sse;
+pub mod stream;
+pub mod websocket;
+pub mod xml;
 
+#[cfg(test)]
+mod test_support;
+
+pub use cassette::{Cassette, CassetteMode};
+pub use compression::Encoding;
+pub use cookie::{Cookie, CookieJar, SameSite};
 pub use error::{Error, Result};
-pub use request::{Method, Request};
+pub use har::Har;
+pub use html::HtmlDocument;
+pub use mock::{MockServer, Stub};
+pub use openapi::OpenApi;
+pub use request::{HttpClient, Method, Request};
 pub use response::Response;
+pub use session::Session;
+pub use snapshot::Snapshot;
+pub use sse::{Event, EventStream};
+pub use stream::StreamingResponse;
+pub use websocket::WebSocket;
+pub use xml::XmlDocument;
@@ -1,9 +1,32 @@
 use clap::{Parser, Subcommand};
-use x_http::error::Result;
+use colored::Colorize;
+use exit_status::ExitStatus;
+use std::io;
+use std::process::ExitCode;
+use std::time::Duration;
+use x_http::error::{Error, Result};
+use x_http::{CookieJar, Request, Response};
 
+mod bench;
 mod config;
+mod curl;
+mod diff;
 mod display;
+mod download;
+mod exit_status;
+mod har_import;
+mod http_file;
 mod interactive;
+mod openapi_import;
+mod output;
+mod postman;
+mod report;
+mod schedule;
+mod stubs;
+mod ws;
+
+#[cfg(test)]
+mod test_support;
 
 use interactive::InteractiveSession;
 
@@ -20,11 +43,66 @@ enum Commands {
     Interactive,
 
     Run {
+        file: Option<String>,
+
+        #[arg(short, long, default_value = "x-http.toml")]
+        config: String,
+
+        #[arg(short, long)]
+        name: Option<String>,
+
+        #[arg(long)]
+        contract: Option<String>,
+
+        #[arg(long)]
+        print_curl: bool,
+
+        #[arg(short, long, value_enum, default_value_t = output::OutputFormat::Human)]
+        output: output::OutputFormat,
+
+        #[arg(long)]
+        report: Option<String>,
+
+        #[arg(long)]
+        fail_on_http_error: bool,
+
+        #[arg(short, long, default_value_t = 1)]
+        parallel: usize,
+
+        #[arg(long)]
+        har: Option<String>,
+
+        #[arg(long)]
+        update_snapshots: bool,
+
+        #[arg(long)]
+        cookie_jar: Option<String>,
+
+        #[arg(short, long)]
+        env: Option<String>,
+    },
+
+    Diff {
+        #[arg(long)]
+        env_a: String,
+
+        #[arg(long)]
+        env_b: String,
+
         #[arg(short, long, default_value = "x-http.toml")]
         config: String,
 
         #[arg(short, long)]
         name: Option<String>,
+
+        #[arg(long)]
+        ignore_path: Vec<String>,
+
+        #[arg(long)]
+        ignore_header: Vec<String>,
+
+        #[arg(short, long, value_enum, default_value_t = output::OutputFormat::Human)]
+        output: output::OutputFormat,
     },
 
     Request {
@@ -40,18 +118,260 @@ enum Commands {
 
         #[arg(short, long)]
         json: bool,
+
+        #[arg(long, conflicts_with = "output")]
+        compressed: bool,
+
+        #[arg(long)]
+        print_curl: bool,
+
+        #[arg(long)]
+        fail_on_http_error: bool,
+
+        #[arg(long)]
+        har: Option<String>,
+
+        #[arg(short, long, conflicts_with = "har")]
+        output: Option<String>,
+
+        #[arg(long, requires = "output")]
+        resume: bool,
+
+        #[arg(long)]
+        cookie_jar: Option<String>,
+    },
+
+    Bench {
+        url: Option<String>,
+
+        #[arg(short = 'X', long, default_value = "GET")]
+        method: String,
+
+        #[arg(short = 'H', long)]
+        header: Vec<String>,
+
+        #[arg(short, long)]
+        body: Option<String>,
+
+        #[arg(short, long)]
+        json: bool,
+
+        #[arg(short, long, default_value = "x-http.toml")]
+        config: String,
+
+        #[arg(short, long)]
+        name: Option<String>,
+
+        #[arg(long, default_value_t = 10)]
+        concurrency: usize,
+
+        #[arg(short, long)]
+        rate: Option<f64>,
+
+        #[arg(short, long)]
+        duration: Option<String>,
+
+        #[arg(long)]
+        requests: Option<usize>,
+
+        #[arg(short, long, value_enum, default_value_t = output::OutputFormat::Human)]
+        output: output::OutputFormat,
+    },
+
+    Sse {
+        url: String,
+
+        #[arg(short = 'H', long)]
+        header: Vec<String>,
+
+        #[arg(long)]
+        last_event_id: Option<String>,
+
+        #[arg(long)]
+        reconnect: bool,
+
+        #[arg(short = 'n', long)]
+        count: Option<usize>,
+
+        #[arg(short, long, value_enum, default_value_t = output::OutputFormat::Human)]
+        output: output::OutputFormat,
+    },
+
+    Ws {
+        url: String,
+
+        #[arg(short = 'H', long)]
+        header: Vec<String>,
+    },
+
+    Mock {
+        stubs: String,
+
+        #[arg(short, long, default_value_t = 8080)]
+        port: u16,
+
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+    },
+
+    Convert {
+        input: String,
+
+        #[arg(short, long, default_value = "x-http.toml")]
+        output: String,
+
+        #[arg(long)]
+        force: bool,
+    },
+
+    Import {
+        #[command(subcommand)]
+        source: ImportSource,
+    },
+
+    Export {
+        #[command(subcommand)]
+        target: ExportTarget,
     },
 }
 
-fn main() -> Result<()> {
+#[derive(Subcommand)]
+enum ImportSource {
+    Postman {
+        collection: String,
+
+        #[arg(short, long)]
+        environment: Option<String>,
+
+        #[arg(short, long, default_value = "x-http.toml")]
+        output: String,
+
+        #[arg(long)]
+        force: bool,
+    },
+
+    Curl {
+        command: String,
+
+        #[arg(short, long)]
+        name: Option<String>,
+
+        #[arg(short, long, default_value = "x-http.toml")]
+        output: String,
+
+        #[arg(long)]
+        run: bool,
+    },
+
+    Openapi {
+        spec: String,
+
+        #[arg(long)]
+        assertions: bool,
+
+        #[arg(short, long, default_value = "x-http.toml")]
+        output: String,
+
+        #[arg(long)]
+        force: bool,
+    },
+
+    Har {
+        file: String,
+
+        #[arg(long)]
+        host: Vec<String>,
+
+        #[arg(long)]
+        mime: Vec<String>,
+
+        #[arg(long)]
+        assertions: bool,
+
+        #[arg(short, long, default_value = "x-http.toml")]
+        output: String,
+
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ExportTarget {
+    Postman {
+        #[arg(short, long, default_value = "x-http.toml")]
+        config: String,
+
+        #[arg(short, long, default_value = "collection.json")]
+        output: String,
+
+        #[arg(short, long, default_value = "x-http")]
+        name: String,
+    },
+}
+
+fn main() -> ExitCode {
     let cli = Cli::parse();
 
+    match run(cli) {
+        Ok(status) => status.into(),
+        Err(e) => {
+            eprintln!("{} {}", "Error:".red().bold(), e);
+            ExitStatus::from_error(&e).into()
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<ExitStatus> {
     match cli.command {
         Some(Commands::Interactive) | None => {
             InteractiveSession::run()?;
         }
-        Some(Commands::Run { config, name }) => {
-            config::run_from_config(&config, name.as_deref())?;
+        Some(Commands::Run {
+            file,
+            config,
+            name,
+            contract,
+            print_curl,
+            output,
+            report,
+            fail_on_http_error,
+            parallel,
+            har,
+            update_snapshots,
+            cookie_jar,
+            env,
+        }) => {
+            let path = file.unwrap_or(config);
+            let options = config::RunOptions {
+                contract: contract.map(x_http::OpenApi::from_file).transpose()?,
+                print_curl,
+                output,
+                report,
+                fail_on_http_error,
+                parallel,
+                har,
+                update_snapshots,
+                cookie_jar,
+                environment: env,
+            };
+            return config::run_from_config(&path, name.as_deref(), &options);
+        }
+        Some(Commands::Diff {
+            env_a,
+            env_b,
+            config,
+            name,
+            ignore_path,
+            ignore_header,
+            output,
+        }) => {
+            let options = diff::DiffOptions {
+                ignore_paths: ignore_path,
+                ignore_headers: ignore_header,
+                output,
+            };
+            return diff::run(&config, &env_a, &env_b, name.as_deref(), &options);
         }
         Some(Commands::Request {
             method,
@@ -59,36 +379,306 @@ fn main() -> Result<()> {
             header,
             body,
             json,
+            compressed,
+            print_curl,
+            fail_on_http_error,
+            har,
+            output,
+            resume,
+            cookie_jar,
+        }) => {
+            let jar = cookie_jar.as_ref().map(CookieJar::load).transpose()?;
+            let mut request = build_quick_request(&method, &url, &header, body.as_deref(), json)?;
+            if let Some(jar) = &jar {
+                request = request.cookie_jar(jar.clone());
+            }
+            if compressed {
+                request = request.accept_compressed();
+            }
+
+            if let Some(path) = output {
+                if print_curl {
+                    println!("{}", request.to_curl());
+                }
+                let status = download::download(request, &path, resume)?;
+                if let (Some(jar), Some(jar_path)) = (&jar, &cookie_jar) {
+                    jar.save(jar_path)?;
+                }
+                if status >= 400 && !(resume && status == download::RANGE_NOT_SATISFIABLE) {
+                    if fail_on_http_error {
+                        eprintln!("{} server returned HTTP {}", "Error:".red().bold(), status);
+                        return Ok(ExitStatus::HttpError);
+                    }
+                    eprintln!(
+                        "⚠️  Server returned HTTP {}, nothing was saved to {}",
+                        status, path
+                    );
+                }
+                return Ok(ExitStatus::Success);
+            }
+
+            let response = quick_request(request, print_curl)?;
+            if let (Some(jar), Some(jar_path)) = (&jar, &cookie_jar) {
+                jar.save(jar_path)?;
+            }
+            if let Some(path) = har {
+                let mut archive = x_http::Har::new();
+                archive.add(&response);
+                archive.save(&path)?;
+            }
+            if fail_on_http_error && response.is_error() {
+                eprintln!(
+                    "{} server returned HTTP {}",
+                    "Error:".red().bold(),
+                    response.status()
+                );
+                return Ok(ExitStatus::HttpError);
+            }
+        }
+        Some(Commands::Bench {
+            url,
+            method,
+            header,
+            body,
+            json,
+            config,
+            name,
+            concurrency,
+            rate,
+            duration,
+            requests,
+            output,
+        }) => {
+            let duration = match (duration, requests) {
+                (Some(duration), _) => Some(bench::parse_duration(&duration)?),
+                (None, Some(_)) => None,
+                (None, None) => Some(Duration::from_secs(10)),
+            };
+            let options = bench::BenchOptions {
+                concurrency,
+                rate,
+                duration,
+                requests,
+            };
+
+            let report = match (&url, &name) {
+                (_, Some(name)) => {
+                    let config = config::Config::load(&config)?;
+                    let request_config = config
+                        .requests
+                        .iter()
+                        .find(|r| &r.name == name)
+                        .ok_or_else(|| {
+                            Error::Config(format!("No requests found with name '{}'", name))
+                        })?;
+                    bench::run(|| config::build_request(&config, request_config), &options)?
+                }
+                (Some(_), None) if body.as_deref() == Some("@-") => {
+                    return Err(Error::Config(
+                        "bench cannot replay a body read from stdin; use --body @file".to_string(),
+                    ))
+                }
+                (Some(url), None) => bench::run(
+                    || build_quick_request(&method, url, &header, body.as_deref(), json),
+                    &options,
+                )?,
+                (None, None) => {
+                    return Err(Error::Config(
+                        "bench needs a URL or --name of a configured request".to_string(),
+                    ))
+                }
+            };
+
+            match output {
+                output::OutputFormat::Human => display::display_bench(&report),
+                output::OutputFormat::Json | output::OutputFormat::Ndjson => {
+                    println!("{}", serde_json::to_string_pretty(&report)?)
+                }
+                _ => {
+                    return Err(Error::Config(
+                        "bench supports human and json output".to_string(),
+                    ))
+                }
+            }
+        }
+        Some(Commands::Sse {
+            url,
+            header,
+            last_event_id,
+            reconnect,
+            count,
+            output,
+        }) => {
+            let request = build_quick_request("GET", &url, &header, None, false)?;
+            let mut builder = x_http::EventStream::builder(request).reconnect(reconnect);
+            if let Some(id) = last_event_id {
+                builder = builder.last_event_id(id);
+            }
+            let stream = builder.connect()?;
+
+            if output.is_human() {
+                println!(
+                    "📡 Streaming events from {} ({})",
+                    url.bold(),
+                    stream.status()
+                );
+            }
+            for event in stream.take(count.unwrap_or(usize::MAX)) {
+                let event = event?;
+                match output {
+                    output::OutputFormat::Human => display::display_event(&event)?,
+                    output::OutputFormat::Json | output::OutputFormat::Ndjson => {
+                        println!("{}", serde_json::to_string(&event)?)
+                    }
+                    _ => {
+                        return Err(Error::Config(
+                            "sse supports human and json output".to_string(),
+                        ))
+                    }
+                }
+            }
+        }
+        Some(Commands::Ws { url, header }) => {
+            ws::repl(&url, &header)?;
+        }
+        Some(Commands::Mock { stubs, port, host }) => {
+            stubs::serve(&stubs, &host, port)?;
+        }
+        Some(Commands::Convert {
+            input,
+            output,
+            force,
         }) => {
-            quick_request(&method, &url, &header, body.as_deref(), json)?;
+            ensure_can_write(&output, force)?;
+            config::convert(&input, &output)?;
         }
+        Some(Commands::Import { source }) => match source {
+            ImportSource::Postman {
+                collection,
+                environment,
+                output,
+                force,
+            } => {
+                ensure_can_write(&output, force)?;
+                let config = postman::import(&collection, environment.as_deref())?;
+                save_imported(&config, &collection, &output)?;
+            }
+            ImportSource::Curl {
+                command,
+                name,
+                output,
+                run,
+            } => {
+                let mut request = curl::parse(&command)?;
+                if let Some(name) = name {
+                    request.name = name;
+                }
+
+                if run {
+                    let response =
+                        config::build_request(&config::Config::default(), &request)?.send()?;
+                    display::display_response(&response)?;
+                } else {
+                    let mut config = if std::path::Path::new(&output).exists() {
+                        config::Config::load(&output)?
+                    } else {
+                        config::Config::default()
+                    };
+                    println!("✅ Added request '{}' to {}", request.name, output);
+                    config.requests.push(request);
+                    config.save(&output)?;
+                }
+            }
+            ImportSource::Openapi {
+                spec,
+                assertions,
+                output,
+                force,
+            } => {
+                ensure_can_write(&output, force)?;
+                let config = openapi_import::import(&spec, assertions)?;
+                save_imported(&config, &spec, &output)?;
+            }
+            ImportSource::Har {
+                file,
+                host,
+                mime,
+                assertions,
+                output,
+                force,
+            } => {
+                ensure_can_write(&output, force)?;
+                let filter = har_import::HarFilter {
+                    hosts: host,
+                    mime_types: mime,
+                    with_assertions: assertions,
+                };
+                let config = har_import::import(&file, &filter)?;
+                save_imported(&config, &file, &output)?;
+            }
+        },
+        Some(Commands::Export { target }) => match target {
+            ExportTarget::Postman {
+                config,
+                output,
+                name,
+            } => {
+                let loaded = config::Config::load(&config)?;
+                let collection = postman::export(&loaded, &name);
+                std::fs::write(&output, serde_json::to_string_pretty(&collection)?)?;
+                println!(
+                    "✅ Exported {} request(s) from {} to {}",
+                    loaded.requests.len(),
+                    config,
+                    output
+                );
+            }
+        },
     }
 
+    Ok(ExitStatus::Success)
+}
+
+fn ensure_can_write(output: &str, force: bool) -> Result<()> {
+    if !force && std::path::Path::new(output).exists() {
+        return Err(Error::Config(format!(
+            "{} already exists; pass --force to overwrite it",
+            output
+        )));
+    }
     Ok(())
 }
 
-fn quick_request(
+fn save_imported(config: &config::Config, source: &str, output: &str) -> Result<()> {
+    config.save(output)?;
+    println!(
+        "✅ Imported {} request(s) from {} to {}",
+        config.requests.len(),
+        source,
+        output
+    );
+    Ok(())
+}
+
+fn quick_request(request: Request, print_curl: bool) -> Result<Response> {
+    if print_curl {
+        println!("{}", request.to_curl());
+    }
+
+    let response = request.send()?;
+    display::display_response(&response)?;
+
+    Ok(response)
+}
+
+fn build_quick_request(
     method: &str,
     url: &str,
     headers: &[String],
     body: Option<&str>,
     is_json: bool,
-) -> Result<()> {
-    use x_http::{Method, Request};
-
-    let method = match method.to_uppercase().as_str() {
-        "GET" => Method::Get,
-        "POST" => Method::Post,
-        "PUT" => Method::Put,
-        "DELETE" => Method::Delete,
-        "PATCH" => Method::Patch,
-        "HEAD" => Method::Head,
-        "OPTIONS" => Method::Options,
-        _ => {
-            eprintln!("Invalid method: {}", method);
-            std::process::exit(1);
-        }
-    };
+) -> Result<Request> {
+    let method = config::parse_method(method)?;
 
     let mut request = Request::new(method, url);
 
@@ -98,7 +688,15 @@ fn quick_request(
         }
     }
 
-    if let Some(body_str) = body {
+    if let Some(source) = body.and_then(|b| b.strip_prefix('@')) {
+        request = match source {
+            "-" => request.body_reader(io::stdin()),
+            path => request.body_file(path),
+        };
+        if is_json {
+            request = request.header("Content-Type", "application/json");
+        }
+    } else if let Some(body_str) = body {
         if is_json {
             let json_value: serde_json::Value = serde_json::from_str(body_str)?;
             request = request.json(&json_value)?;
@@ -107,8 +705,5 @@ fn quick_request(
         }
     }
 
-    let response = request.send()?;
-    display::display_response(&response)?;
-
-    Ok(())
+    Ok(request)
 }
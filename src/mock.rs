@@ -0,0 +1,579 @@
+use crate::error::{Error, Result};
+use crate::request::Method;
+use reqwest::StatusCode;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_BODY_SIZE: usize = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+enum BodyMatcher {
+    Exact(Vec<u8>),
+    Contains(String),
+    Json(Value),
+}
+
+#[derive(Debug, Clone)]
+pub struct Stub {
+    method: Option<Method>,
+    path: String,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    body: Option<BodyMatcher>,
+    status: u16,
+    response_headers: Vec<(String, String)>,
+    response_body: Vec<u8>,
+    delay: Option<Duration>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReceivedRequest {
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub headers: BTreeMap<String, String>,
+    pub body: Vec<u8>,
+    pub status: u16,
+}
+
+#[derive(Debug)]
+struct StubState {
+    stub: Stub,
+    calls: AtomicUsize,
+}
+
+#[derive(Debug, Default)]
+struct ServerState {
+    stubs: Mutex<Vec<Arc<StubState>>>,
+    received: Mutex<Vec<ReceivedRequest>>,
+}
+
+#[derive(Debug)]
+pub struct MockServer {
+    address: SocketAddr,
+    state: Arc<ServerState>,
+    shutdown: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MockHandle {
+    state: Arc<StubState>,
+}
+
+impl Stub {
+    pub fn new(method: Method, path: impl Into<String>) -> Self {
+        Self {
+            method: Some(method),
+            ..Self::any(path)
+        }
+    }
+
+    pub fn any(path: impl Into<String>) -> Self {
+        Self {
+            method: None,
+            path: path.into(),
+            query: Vec::new(),
+            headers: Vec::new(),
+            body: None,
+            status: 200,
+            response_headers: Vec::new(),
+            response_body: Vec::new(),
+            delay: None,
+        }
+    }
+
+    pub fn get(path: impl Into<String>) -> Self {
+        Self::new(Method::Get, path)
+    }
+
+    pub fn post(path: impl Into<String>) -> Self {
+        Self::new(Method::Post, path)
+    }
+
+    pub fn put(path: impl Into<String>) -> Self {
+        Self::new(Method::Put, path)
+    }
+
+    pub fn delete(path: impl Into<String>) -> Self {
+        Self::new(Method::Delete, path)
+    }
+
+    pub fn patch(path: impl Into<String>) -> Self {
+        Self::new(Method::Patch, path)
+    }
+
+    pub fn query(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.query.push((key.into(), value.into()));
+        self
+    }
+
+    pub fn header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((key.into().to_lowercase(), value.into()));
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = Some(BodyMatcher::Exact(body.into()));
+        self
+    }
+
+    pub fn body_contains(mut self, text: impl Into<String>) -> Self {
+        self.body = Some(BodyMatcher::Contains(text.into()));
+        self
+    }
+
+    pub fn json_body<T: Serialize>(mut self, json: &T) -> Result<Self> {
+        self.body = Some(BodyMatcher::Json(serde_json::to_value(json)?));
+        Ok(self)
+    }
+
+    pub fn status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn response_header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.response_headers.push((key.into(), value.into()));
+        self
+    }
+
+    pub fn response_body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.response_body = body.into();
+        self
+    }
+
+    pub fn response_json<T: Serialize>(mut self, json: &T) -> Result<Self> {
+        self.response_body = serde_json::to_vec(json)?;
+        self.response_headers
+            .retain(|(key, _)| !key.eq_ignore_ascii_case("content-type"));
+        self.response_headers
+            .push(("Content-Type".to_string(), "application/json".to_string()));
+        Ok(self)
+    }
+
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{} {}",
+            self.method.map_or("ANY", |m| m.as_str()),
+            self.path
+        )
+    }
+
+    fn matches(&self, request: &ReceivedRequest) -> bool {
+        if self
+            .method
+            .is_some_and(|method| !method.as_str().eq_ignore_ascii_case(&request.method))
+        {
+            return false;
+        }
+        if self.path != request.path {
+            return false;
+        }
+        if !self.query.iter().all(|pair| request.query.contains(pair)) {
+            return false;
+        }
+        if !self
+            .headers
+            .iter()
+            .all(|(key, value)| request.headers.get(key) == Some(value))
+        {
+            return false;
+        }
+
+        match &self.body {
+            None => true,
+            Some(BodyMatcher::Exact(body)) => *body == request.body,
+            Some(BodyMatcher::Contains(text)) => {
+                String::from_utf8_lossy(&request.body).contains(text.as_str())
+            }
+            Some(BodyMatcher::Json(expected)) => {
+                serde_json::from_slice::<Value>(&request.body).is_ok_and(|json| json == *expected)
+            }
+        }
+    }
+}
+
+impl MockServer {
+    pub fn start() -> Result<Self> {
+        Self::start_on("127.0.0.1:0")
+    }
+
+    pub fn start_on(address: &str) -> Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        let address = listener.local_addr()?;
+        let state = Arc::new(ServerState::default());
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let state = Arc::clone(&state);
+            let shutdown = Arc::clone(&shutdown);
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if shutdown.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        let state = Arc::clone(&state);
+                        thread::spawn(move || handle_connection(stream, &state));
+                    }
+                }
+            })
+        };
+
+        Ok(Self {
+            address,
+            state,
+            shutdown,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    pub fn uri(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.uri(), path)
+    }
+
+    pub fn mock(&self, stub: Stub) -> MockHandle {
+        let state = Arc::new(StubState {
+            stub,
+            calls: AtomicUsize::new(0),
+        });
+        lock(&self.state.stubs).push(Arc::clone(&state));
+        MockHandle { state }
+    }
+
+    pub fn reset(&self) {
+        lock(&self.state.stubs).clear();
+        lock(&self.state.received).clear();
+    }
+
+    pub fn received_requests(&self) -> Vec<ReceivedRequest> {
+        lock(&self.state.received).clone()
+    }
+
+    pub fn take_received_requests(&self) -> Vec<ReceivedRequest> {
+        std::mem::take(&mut *lock(&self.state.received))
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        let _ = TcpStream::connect(self.address);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl MockHandle {
+    pub fn calls(&self) -> usize {
+        self.state.calls.load(Ordering::SeqCst)
+    }
+
+    pub fn expect_called(&self, times: usize) -> Result<()> {
+        let calls = self.calls();
+        if calls != times {
+            return Err(Error::Assertion(format!(
+                "Stub {} expected {} call(s), got {}",
+                self.state.stub.describe(),
+                times,
+                calls
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn handle_connection(stream: TcpStream, state: &ServerState) {
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    let mut request = match read_request(&stream) {
+        Ok(request) => request,
+        Err(e) if e.kind() == ErrorKind::FileTooLarge => {
+            let headers = [("Content-Type".to_string(), "text/plain".to_string())];
+            let _ = write_response(stream, 413, &headers, e.to_string().as_bytes());
+            return;
+        }
+        Err(_) => return,
+    };
+
+    let stub = lock(&state.stubs)
+        .iter()
+        .find(|stub| stub.stub.matches(&request))
+        .cloned();
+
+    let (status, headers, body) = match &stub {
+        Some(stub) => {
+            stub.calls.fetch_add(1, Ordering::SeqCst);
+            if let Some(delay) = stub.stub.delay {
+                thread::sleep(delay);
+            }
+            (
+                stub.stub.status,
+                stub.stub.response_headers.clone(),
+                stub.stub.response_body.clone(),
+            )
+        }
+        None => (
+            404,
+            vec![("Content-Type".to_string(), "text/plain".to_string())],
+            format!("No stub matches {} {}", request.method, request.path).into_bytes(),
+        ),
+    };
+
+    request.status = status;
+    lock(&state.received).push(request);
+
+    let _ = write_response(stream, status, &headers, &body);
+}
+
+fn read_request(stream: &TcpStream) -> std::io::Result<ReceivedRequest> {
+    let invalid = |message: &str| std::io::Error::new(ErrorKind::InvalidData, message);
+    let mut reader = BufReader::new(stream);
+
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().ok_or_else(|| invalid("missing method"))?;
+    let target = parts.next().ok_or_else(|| invalid("missing path"))?;
+
+    let url = url::Url::parse(&format!("http://localhost{}", target))
+        .map_err(|_| invalid("invalid request target"))?;
+    let mut request = ReceivedRequest {
+        method: method.to_uppercase(),
+        path: url.path().to_string(),
+        query: url
+            .query_pairs()
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect(),
+        headers: BTreeMap::new(),
+        body: Vec::new(),
+        status: 0,
+    };
+
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((key, value)) = header.split_once(':') {
+            request
+                .headers
+                .insert(key.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    if let Some(length) = request.headers.get("content-length") {
+        let length: usize = length
+            .parse()
+            .map_err(|_| invalid("invalid content-length"))?;
+        read_body(&mut reader, &mut request.body, length)?;
+    } else if request
+        .headers
+        .get("transfer-encoding")
+        .is_some_and(|encoding| encoding.eq_ignore_ascii_case("chunked"))
+    {
+        request.body = read_chunked(&mut reader)?;
+    }
+
+    Ok(request)
+}
+
+fn read_chunked(reader: &mut impl BufRead) -> std::io::Result<Vec<u8>> {
+    let mut body = Vec::new();
+    loop {
+        let mut size = String::new();
+        reader.read_line(&mut size)?;
+        let size = usize::from_str_radix(size.trim().split(';').next().unwrap_or("0"), 16)
+            .map_err(|_| std::io::Error::new(ErrorKind::InvalidData, "invalid chunk"))?;
+
+        read_body(reader, &mut body, size)?;
+        reader.read_exact(&mut [0; 2])?;
+        if size == 0 {
+            return Ok(body);
+        }
+    }
+}
+
+fn read_body(reader: &mut impl Read, body: &mut Vec<u8>, length: usize) -> std::io::Result<()> {
+    if body
+        .len()
+        .checked_add(length)
+        .is_none_or(|total| total > MAX_BODY_SIZE)
+    {
+        return Err(std::io::Error::new(
+            ErrorKind::FileTooLarge,
+            format!("Request body exceeds the {} byte limit", MAX_BODY_SIZE),
+        ));
+    }
+    let read = reader.take(length as u64).read_to_end(body)?;
+    if read < length {
+        return Err(ErrorKind::UnexpectedEof.into());
+    }
+    Ok(())
+}
+
+fn write_response(
+    mut stream: TcpStream,
+    status: u16,
+    headers: &[(String, String)],
+    body: &[u8],
+) -> std::io::Result<()> {
+    let reason = StatusCode::from_u16(status)
+        .ok()
+        .and_then(|s| s.canonical_reason())
+        .unwrap_or("");
+
+    let mut head = format!("HTTP/1.1 {} {}\r\n", status, reason);
+    for (key, value) in headers {
+        head.push_str(&format!("{}: {}\r\n", key, value));
+    }
+    head.push_str(&format!(
+        "Content-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    ));
+
+    stream.write_all(head.as_bytes())?;
+    stream.write_all(body)?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Request;
+    use serde_json::json;
+
+    #[test]
+    fn test_stub_matching_and_verification() {
+        let server = MockServer::start().unwrap();
+        let users = server.mock(
+            Stub::get("/users")
+                .query("page", "2")
+                .header("Authorization", "Bearer t")
+                .response_json(&json!({"users": [{"id": 1}]}))
+                .unwrap(),
+        );
+        let create = server.mock(
+            Stub::post("/users")
+                .json_body(&json!({"name": "John"}))
+                .unwrap()
+                .status(201)
+                .response_header("Location", "/users/2"),
+        );
+
+        Request::get(server.url("/users"))
+            .query("page", "2")
+            .header("Authorization", "Bearer t")
+            .send()
+            .unwrap()
+            .expect_status(200)
+            .unwrap()
+            .expect_json()
+            .unwrap()
+            .assert_field("users[0].id", 1)
+            .unwrap();
+
+        Request::post(server.url("/users"))
+            .json(&json!({"name": "John"}))
+            .unwrap()
+            .send()
+            .unwrap()
+            .expect_status(201)
+            .unwrap()
+            .expect_header("location", "/users/2")
+            .unwrap();
+
+        let unmatched = Request::get(server.url("/users")).send().unwrap();
+        assert_eq!(unmatched.status(), 404);
+        assert!(unmatched
+            .text()
+            .unwrap()
+            .contains("No stub matches GET /users"));
+
+        users.expect_called(1).unwrap();
+        create.expect_called(1).unwrap();
+        assert!(create.expect_called(2).is_err());
+
+        let received = server.received_requests();
+        assert_eq!(received.len(), 3);
+        assert_eq!(received[1].body, br#"{"name":"John"}"#);
+        assert_eq!(received[2].status, 404);
+
+        assert_eq!(server.take_received_requests().len(), 3);
+        assert!(server.received_requests().is_empty());
+    }
+
+    #[test]
+    fn test_stub_delay() {
+        let server = MockServer::start().unwrap();
+        server.mock(
+            Stub::any("/slow")
+                .delay(Duration::from_millis(200))
+                .response_body("done"),
+        );
+
+        let response = Request::delete(server.url("/slow"))
+            .timeout(Duration::from_millis(50))
+            .send();
+        assert!(response.is_err());
+
+        let response = Request::delete(server.url("/slow")).send().unwrap();
+        assert!(response.duration() >= Duration::from_millis(200));
+        assert_eq!(response.text().unwrap(), "done");
+    }
+
+    #[test]
+    fn test_oversized_body_rejected() {
+        let server = MockServer::start().unwrap();
+        server.mock(Stub::post("/upload").response_body("ok"));
+
+        let send = |request: String| {
+            let mut stream = TcpStream::connect(server.address()).unwrap();
+            stream.write_all(request.as_bytes()).unwrap();
+            let mut response = String::new();
+            let _ = stream.read_to_string(&mut response);
+            response
+        };
+
+        let response = send(format!(
+            "POST /upload HTTP/1.1\r\nHost: x\r\nContent-Length: {}\r\n\r\n",
+            usize::MAX
+        ));
+        assert!(response.starts_with("HTTP/1.1 413"));
+
+        let response = send(format!(
+            "POST /upload HTTP/1.1\r\nHost: x\r\nTransfer-Encoding: chunked\r\n\r\n{:x}\r\n",
+            usize::MAX
+        ));
+        assert!(response.starts_with("HTTP/1.1 413"));
+        assert!(server.received_requests().is_empty());
+    }
+}
@@ -0,0 +1,913 @@
+use crate::error::{Error, Result};
+use crate::request::Method;
+use crate::response::Response;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+const MAX_SCHEMA_DEPTH: usize = 16;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpenApi {
+    pub openapi: String,
+    pub info: Info,
+    #[serde(default)]
+    pub servers: Vec<Server>,
+    #[serde(default)]
+    pub paths: BTreeMap<String, PathItem>,
+    #[serde(default)]
+    pub components: Components,
+    #[serde(default)]
+    pub security: Vec<SecurityRequirement>,
+    #[serde(skip)]
+    raw: Value,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Info {
+    pub title: String,
+    #[serde(default)]
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Server {
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PathItem {
+    pub get: Option<Operation>,
+    pub put: Option<Operation>,
+    pub post: Option<Operation>,
+    pub delete: Option<Operation>,
+    pub options: Option<Operation>,
+    pub head: Option<Operation>,
+    pub patch: Option<Operation>,
+    #[serde(default)]
+    pub parameters: Vec<Parameter>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Operation {
+    pub operation_id: Option<String>,
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub parameters: Vec<Parameter>,
+    pub request_body: Option<RequestBody>,
+    #[serde(default)]
+    pub responses: BTreeMap<String, ApiResponse>,
+    pub security: Option<Vec<SecurityRequirement>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Parameter {
+    #[serde(rename = "$ref")]
+    pub reference: Option<String>,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, rename = "in")]
+    pub location: String,
+    #[serde(default)]
+    pub required: bool,
+    pub schema: Option<Value>,
+    pub example: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RequestBody {
+    #[serde(rename = "$ref")]
+    pub reference: Option<String>,
+    #[serde(default)]
+    pub content: BTreeMap<String, MediaType>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ApiResponse {
+    #[serde(rename = "$ref")]
+    pub reference: Option<String>,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub headers: BTreeMap<String, HeaderObject>,
+    #[serde(default)]
+    pub content: BTreeMap<String, MediaType>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HeaderObject {
+    #[serde(default)]
+    pub required: bool,
+    pub schema: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MediaType {
+    pub schema: Option<Value>,
+    pub example: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Components {
+    #[serde(default)]
+    pub schemas: BTreeMap<String, Value>,
+    #[serde(default)]
+    pub security_schemes: BTreeMap<String, SecurityScheme>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecurityScheme {
+    #[serde(rename = "type")]
+    pub scheme_type: String,
+    pub scheme: Option<String>,
+    pub name: Option<String>,
+    #[serde(rename = "in")]
+    pub location: Option<String>,
+}
+
+pub type SecurityRequirement = BTreeMap<String, Vec<String>>;
+
+#[derive(Debug, Clone, Copy)]
+pub struct OperationRef<'a> {
+    pub path: &'a str,
+    pub method: Method,
+    pub operation: &'a Operation,
+    pub path_item: &'a PathItem,
+}
+
+impl OpenApi {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        Self::parse(&contents)
+    }
+
+    pub fn parse(contents: &str) -> Result<Self> {
+        let raw: Value = serde_yaml::from_str(contents)
+            .map_err(|e| Error::OpenApi(format!("Failed to parse document: {}", e)))?;
+        let mut spec: OpenApi = serde_json::from_value(raw.clone())
+            .map_err(|e| Error::OpenApi(format!("Invalid OpenAPI document: {}", e)))?;
+        spec.raw = raw;
+        Ok(spec)
+    }
+
+    pub fn base_url(&self) -> Option<&str> {
+        self.servers.first().map(|s| s.url.as_str())
+    }
+
+    pub fn operations(&self) -> Vec<OperationRef<'_>> {
+        let mut operations = Vec::new();
+
+        for (path, item) in &self.paths {
+            let methods = [
+                (Method::Get, &item.get),
+                (Method::Post, &item.post),
+                (Method::Put, &item.put),
+                (Method::Patch, &item.patch),
+                (Method::Delete, &item.delete),
+                (Method::Head, &item.head),
+                (Method::Options, &item.options),
+            ];
+
+            for (method, operation) in methods {
+                if let Some(operation) = operation {
+                    operations.push(OperationRef {
+                        path,
+                        method,
+                        operation,
+                        path_item: item,
+                    });
+                }
+            }
+        }
+
+        operations
+    }
+
+    pub fn operation(&self, operation: &str) -> Option<OperationRef<'_>> {
+        let by_route = operation
+            .split_once(' ')
+            .map(|(method, path)| (method.to_uppercase(), path.trim()));
+
+        self.operations().into_iter().find(|op| {
+            op.operation.operation_id.as_deref() == Some(operation)
+                || by_route
+                    .as_ref()
+                    .is_some_and(|(method, path)| op.method.as_str() == method && op.path == *path)
+        })
+    }
+
+    pub fn match_operation(&self, method: Method, url: &str) -> Option<OperationRef<'_>> {
+        let path = url::Url::parse(url)
+            .map(|u| u.path().to_string())
+            .unwrap_or_else(|_| url.split('?').next().unwrap_or(url).to_string());
+
+        let candidates: Vec<&str> = self
+            .servers
+            .iter()
+            .filter_map(|server| {
+                let base = url::Url::parse(&server.url)
+                    .map(|u| u.path().to_string())
+                    .unwrap_or_else(|_| server.url.clone());
+                path.strip_prefix(base.trim_end_matches('/'))
+            })
+            .chain(std::iter::once(path.as_str()))
+            .collect();
+
+        self.operations()
+            .into_iter()
+            .filter(|op| op.method == method)
+            .filter_map(|op| {
+                candidates
+                    .iter()
+                    .filter_map(|candidate| path_template_score(op.path, candidate))
+                    .max()
+                    .map(|score| (score, op))
+            })
+            .max_by_key(|(score, _)| *score)
+            .map(|(_, op)| op)
+    }
+
+    pub fn response_violations(
+        &self,
+        op: &OperationRef<'_>,
+        response: &Response,
+    ) -> Result<Vec<String>> {
+        let mut violations = Vec::new();
+        let status = response.status().to_string();
+        let range = format!("{}XX", &status[..1]);
+
+        let declared = op
+            .operation
+            .responses
+            .keys()
+            .find(|code| **code == status)
+            .or_else(|| {
+                op.operation
+                    .responses
+                    .keys()
+                    .find(|code| code.eq_ignore_ascii_case(&range))
+            })
+            .or_else(|| {
+                op.operation
+                    .responses
+                    .keys()
+                    .find(|code| *code == "default")
+            });
+
+        let Some(declared) = declared else {
+            let codes: Vec<&str> = op.operation.responses.keys().map(String::as_str).collect();
+            violations.push(format!(
+                "status {} is not declared (declared: {})",
+                status,
+                codes.join(", ")
+            ));
+            return Ok(violations);
+        };
+
+        let Some(spec_response) = self.response(op, declared)? else {
+            return Ok(violations);
+        };
+
+        for (name, header) in &spec_response.headers {
+            if header.required
+                && !name.eq_ignore_ascii_case("content-type")
+                && response.header(name).is_none()
+            {
+                violations.push(format!("required header '{}' is missing", name));
+            }
+        }
+
+        if spec_response.content.is_empty() || response.body_bytes().is_empty() {
+            return Ok(violations);
+        }
+
+        let Some(content_type) = response.header("content-type") else {
+            violations.push("response has a body but no Content-Type header".to_string());
+            return Ok(violations);
+        };
+        let essence = content_type.split(';').next().unwrap_or("").trim();
+
+        let media = spec_response
+            .content
+            .iter()
+            .find(|(declared, _)| media_type_matches(declared, essence));
+
+        let Some((declared_type, media)) = media else {
+            let declared: Vec<&str> = spec_response.content.keys().map(String::as_str).collect();
+            violations.push(format!(
+                "content type '{}' is not declared (declared: {})",
+                essence,
+                declared.join(", ")
+            ));
+            return Ok(violations);
+        };
+
+        if let (Some(schema), true) = (&media.schema, declared_type.contains("json")) {
+            match response.json_value() {
+                Ok(body) => violations.extend(self.validate(schema, &body)),
+                Err(e) => violations.push(format!("body is not valid JSON: {}", e)),
+            }
+        }
+
+        Ok(violations)
+    }
+
+    pub fn resolve_ref(&self, reference: &str) -> Option<&Value> {
+        self.raw.pointer(reference.strip_prefix('#')?)
+    }
+
+    fn resolve_typed<T: DeserializeOwned>(&self, reference: &str) -> Result<T> {
+        let value = self
+            .resolve_ref(reference)
+            .ok_or_else(|| Error::OpenApi(format!("Unresolved reference: {}", reference)))?;
+        serde_json::from_value(value.clone())
+            .map_err(|e| Error::OpenApi(format!("Invalid object at {}: {}", reference, e)))
+    }
+
+    pub fn parameters(&self, op: &OperationRef<'_>) -> Result<Vec<Parameter>> {
+        let mut parameters: Vec<Parameter> = Vec::new();
+
+        for parameter in op
+            .path_item
+            .parameters
+            .iter()
+            .chain(&op.operation.parameters)
+        {
+            let parameter = match &parameter.reference {
+                Some(reference) => self.resolve_typed(reference)?,
+                None => parameter.clone(),
+            };
+            parameters.retain(|p| !(p.name == parameter.name && p.location == parameter.location));
+            parameters.push(parameter);
+        }
+
+        Ok(parameters)
+    }
+
+    pub fn request_body(&self, op: &OperationRef<'_>) -> Result<Option<RequestBody>> {
+        match &op.operation.request_body {
+            Some(RequestBody {
+                reference: Some(reference),
+                ..
+            }) => self.resolve_typed(reference).map(Some),
+            other => Ok(other.clone()),
+        }
+    }
+
+    pub fn response(&self, op: &OperationRef<'_>, status: &str) -> Result<Option<ApiResponse>> {
+        match op.operation.responses.get(status) {
+            Some(ApiResponse {
+                reference: Some(reference),
+                ..
+            }) => self.resolve_typed(reference).map(Some),
+            other => Ok(other.cloned()),
+        }
+    }
+
+    pub fn security_schemes(&self, op: &OperationRef<'_>) -> Vec<(&str, &SecurityScheme)> {
+        let requirements = op.operation.security.as_ref().unwrap_or(&self.security);
+
+        requirements
+            .first()
+            .into_iter()
+            .flat_map(|requirement| requirement.keys())
+            .filter_map(|name| {
+                self.components
+                    .security_schemes
+                    .get_key_value(name)
+                    .map(|(k, v)| (k.as_str(), v))
+            })
+            .collect()
+    }
+
+    pub fn inline_schema(&self, schema: &Value) -> Value {
+        inline_refs(schema, &self.raw, 0)
+    }
+
+    pub fn example_for(&self, schema: &Value) -> Value {
+        example_for_schema(schema, &self.raw, 0)
+    }
+
+    pub fn validate(&self, schema: &Value, instance: &Value) -> Vec<String> {
+        let mut errors = Vec::new();
+        validate_value(schema, instance, &self.raw, "$", 0, &mut errors);
+        errors
+    }
+}
+
+pub fn validate_schema(schema: &Value, instance: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_value(schema, instance, schema, "$", 0, &mut errors);
+    errors
+}
+
+fn path_template_score(template: &str, path: &str) -> Option<usize> {
+    let template: Vec<&str> = template.trim_matches('/').split('/').collect();
+    let path: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    if template.len() != path.len() {
+        return None;
+    }
+
+    let mut literal_segments = 0;
+    for (expected, actual) in template.iter().zip(&path) {
+        if expected.starts_with('{') && expected.ends_with('}') {
+            if actual.is_empty() {
+                return None;
+            }
+        } else if expected == actual {
+            literal_segments += 1;
+        } else {
+            return None;
+        }
+    }
+
+    Some(literal_segments)
+}
+
+fn media_type_matches(declared: &str, actual: &str) -> bool {
+    let declared = declared.split(';').next().unwrap_or("").trim();
+
+    match declared.split_once('/') {
+        Some(("*", "*")) => true,
+        Some((kind, "*")) => actual
+            .split_once('/')
+            .is_some_and(|(actual_kind, _)| actual_kind.eq_ignore_ascii_case(kind)),
+        _ => declared.eq_ignore_ascii_case(actual),
+    }
+}
+
+fn resolve<'a>(schema: &'a Value, root: &'a Value) -> &'a Value {
+    let mut current = schema;
+    for _ in 0..MAX_SCHEMA_DEPTH {
+        match current
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|r| r.strip_prefix('#'))
+            .and_then(|pointer| root.pointer(pointer))
+        {
+            Some(target) => current = target,
+            None => break,
+        }
+    }
+    current
+}
+
+fn inline_refs(schema: &Value, root: &Value, depth: usize) -> Value {
+    if depth > MAX_SCHEMA_DEPTH {
+        return Value::Object(Map::new());
+    }
+
+    match resolve(schema, root) {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k.clone(), inline_refs(v, root, depth + 1)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .filter(|v| !v.is_null())
+                .map(|v| inline_refs(v, root, depth + 1))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn example_for_schema(schema: &Value, root: &Value, depth: usize) -> Value {
+    let schema = resolve(schema, root);
+
+    if depth > MAX_SCHEMA_DEPTH {
+        return Value::Null;
+    }
+    if let Some(example) = schema.get("example").or_else(|| schema.get("default")) {
+        return example.clone();
+    }
+    if let Some(first) = schema
+        .get("enum")
+        .and_then(Value::as_array)
+        .and_then(|e| e.first())
+    {
+        return first.clone();
+    }
+    if let Some(all_of) = schema.get("allOf").and_then(Value::as_array) {
+        let mut merged = Map::new();
+        for part in all_of {
+            if let Value::Object(map) = example_for_schema(part, root, depth + 1) {
+                merged.extend(map);
+            }
+        }
+        return Value::Object(merged);
+    }
+    if let Some(first) = schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))
+        .and_then(Value::as_array)
+        .and_then(|variants| variants.first())
+    {
+        return example_for_schema(first, root, depth + 1);
+    }
+
+    match schema_type(schema) {
+        Some("object") => {
+            let properties = schema
+                .get("properties")
+                .and_then(Value::as_object)
+                .map(|props| {
+                    props
+                        .iter()
+                        .map(|(k, v)| (k.clone(), example_for_schema(v, root, depth + 1)))
+                        .collect()
+                })
+                .unwrap_or_default();
+            Value::Object(properties)
+        }
+        Some("array") => {
+            let item = schema
+                .get("items")
+                .map(|items| example_for_schema(items, root, depth + 1))
+                .unwrap_or(Value::Null);
+            Value::Array(vec![item])
+        }
+        Some("string") => Value::String(
+            match schema.get("format").and_then(Value::as_str) {
+                Some("date-time") => "2024-01-01T00:00:00Z",
+                Some("date") => "2024-01-01",
+                Some("email") => "user@example.com",
+                Some("uuid") => "00000000-0000-0000-0000-000000000000",
+                Some("uri") | Some("url") => "https://example.com",
+                _ => "string",
+            }
+            .to_string(),
+        ),
+        Some("integer") => Value::from(0),
+        Some("number") => Value::from(0.0),
+        Some("boolean") => Value::Bool(true),
+        _ => Value::Null,
+    }
+}
+
+fn schema_type(schema: &Value) -> Option<&str> {
+    match schema.get("type") {
+        Some(Value::String(t)) => Some(t),
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|t| *t != "null"),
+        _ if schema.get("properties").is_some() => Some("object"),
+        _ if schema.get("items").is_some() => Some("array"),
+        _ => None,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn type_matches(expected: &str, value: &Value) -> bool {
+    match (expected, value) {
+        ("number", Value::Number(_)) => true,
+        ("integer", Value::Number(n)) => {
+            n.is_i64() || n.is_u64() || n.as_f64().is_some_and(|f| f.fract() == 0.0)
+        }
+        (expected, value) => expected == type_name(value),
+    }
+}
+
+fn validate_value(
+    schema: &Value,
+    instance: &Value,
+    root: &Value,
+    path: &str,
+    depth: usize,
+    errors: &mut Vec<String>,
+) {
+    let schema = resolve(schema, root);
+    if depth > MAX_SCHEMA_DEPTH || !schema.is_object() {
+        return;
+    }
+
+    if instance.is_null() {
+        let nullable = schema.get("nullable").and_then(Value::as_bool) == Some(true)
+            || schema
+                .get("type")
+                .and_then(Value::as_array)
+                .is_some_and(|types| types.iter().any(|t| t == "null"));
+        if nullable {
+            return;
+        }
+    }
+
+    if let Some(all_of) = schema.get("allOf").and_then(Value::as_array) {
+        for part in all_of {
+            validate_value(part, instance, root, path, depth + 1, errors);
+        }
+    }
+
+    for keyword in ["oneOf", "anyOf"] {
+        if let Some(variants) = schema.get(keyword).and_then(Value::as_array) {
+            let matches = variants
+                .iter()
+                .filter(|variant| {
+                    let mut variant_errors = Vec::new();
+                    validate_value(
+                        variant,
+                        instance,
+                        root,
+                        path,
+                        depth + 1,
+                        &mut variant_errors,
+                    );
+                    variant_errors.is_empty()
+                })
+                .count();
+            let valid = if keyword == "oneOf" {
+                matches == 1
+            } else {
+                matches > 0
+            };
+            if !valid {
+                errors.push(format!(
+                    "{}: expected to match {} of {} schemas, matched {}",
+                    path,
+                    if keyword == "oneOf" {
+                        "exactly one"
+                    } else {
+                        "at least one"
+                    },
+                    variants.len(),
+                    matches
+                ));
+            }
+        }
+    }
+
+    if let Some(expected) = schema_type(schema) {
+        let declared = schema.get("type").is_some();
+        if declared && !type_matches(expected, instance) {
+            errors.push(format!(
+                "{}: expected {}, got {}",
+                path,
+                expected,
+                type_name(instance)
+            ));
+            return;
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(instance) {
+            errors.push(format!(
+                "{}: value {} is not one of {:?}",
+                path, instance, allowed
+            ));
+        }
+    }
+
+    match instance {
+        Value::Object(map) => {
+            if let Some(required) = schema.get("required").and_then(Value::as_array) {
+                for key in required.iter().filter_map(Value::as_str) {
+                    if !map.contains_key(key) {
+                        errors.push(format!("{}: missing required property '{}'", path, key));
+                    }
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (key, value) in map {
+                let child_path = format!("{}.{}", path, key);
+                match properties.and_then(|p| p.get(key)) {
+                    Some(property) => {
+                        validate_value(property, value, root, &child_path, depth + 1, errors)
+                    }
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            errors.push(format!("{}: additional property not allowed", child_path))
+                        }
+                        Some(additional @ Value::Object(_)) => {
+                            validate_value(additional, value, root, &child_path, depth + 1, errors)
+                        }
+                        _ => {}
+                    },
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                if (items.len() as u64) < min {
+                    errors.push(format!("{}: expected at least {} items", path, min));
+                }
+            }
+            if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+                if items.len() as u64 > max {
+                    errors.push(format!("{}: expected at most {} items", path, max));
+                }
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    let child_path = format!("{}[{}]", path, index);
+                    validate_value(item_schema, item, root, &child_path, depth + 1, errors);
+                }
+            }
+        }
+        Value::String(s) => {
+            let length = s.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                if length < min {
+                    errors.push(format!("{}: expected length >= {}", path, min));
+                }
+            }
+            if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+                if length > max {
+                    errors.push(format!("{}: expected length <= {}", path, max));
+                }
+            }
+        }
+        Value::Number(n) => {
+            let n = n.as_f64().unwrap_or_default();
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+                if n < min {
+                    errors.push(format!("{}: expected >= {}", path, min));
+                }
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+                if n > max {
+                    errors.push(format!("{}: expected <= {}", path, max));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+    use serde_json::json;
+
+    const SPEC: &str = r##"
+openapi: 3.0.3
+info:
+  title: Users API
+  version: "1.0"
+servers:
+  - url: https://api.example.com/v1
+security:
+  - bearerAuth: []
+paths:
+  /users/{id}:
+    parameters:
+      - name: id
+        in: path
+        required: true
+        schema:
+          type: integer
+          example: 42
+    get:
+      operationId: getUser
+      responses:
+        "200":
+          description: A user
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/User"
+components:
+  securitySchemes:
+    bearerAuth:
+      type: http
+      scheme: bearer
+  schemas:
+    User:
+      type: object
+      required: [id, name]
+      properties:
+        id:
+          type: integer
+        name:
+          type: string
+        email:
+          type: string
+          format: email
+          nullable: true
+"##;
+
+    #[test]
+    fn test_parse_and_resolve() {
+        let spec = OpenApi::parse(SPEC).unwrap();
+        assert_eq!(spec.base_url(), Some("https://api.example.com/v1"));
+
+        let op = spec.operation("getUser").unwrap();
+        assert_eq!(op.path, "/users/{id}");
+        assert_eq!(op.method, Method::Get);
+
+        let parameters = spec.parameters(&op).unwrap();
+        assert_eq!(parameters[0].name, "id");
+
+        let schemes = spec.security_schemes(&op);
+        assert_eq!(schemes[0].0, "bearerAuth");
+
+        let response = spec.response(&op, "200").unwrap().unwrap();
+        let schema = response.content["application/json"]
+            .schema
+            .as_ref()
+            .unwrap();
+        assert_eq!(
+            spec.example_for(schema),
+            json!({"id": 0, "name": "string", "email": "user@example.com"})
+        );
+    }
+
+    #[test]
+    fn test_match_operation() {
+        let spec = OpenApi::parse(SPEC).unwrap();
+
+        let op = spec
+            .match_operation(Method::Get, "https://api.example.com/v1/users/42?x=1")
+            .unwrap();
+        assert_eq!(op.operation.operation_id.as_deref(), Some("getUser"));
+
+        assert!(spec
+            .match_operation(Method::Post, "https://api.example.com/v1/users/42")
+            .is_none());
+        assert!(spec.operation("GET /users/{id}").is_some());
+    }
+
+    #[test]
+    fn test_response_violations() {
+        use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+
+        let spec = OpenApi::parse(SPEC).unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+        let ok = Response::from_parts(
+            StatusCode::OK,
+            headers.clone(),
+            r#"{"id": 1, "name": "John"}"#,
+        );
+        assert!(ok.expect_conforms_to(&spec, "getUser").is_ok());
+
+        let wrong_body = Response::from_parts(StatusCode::OK, headers.clone(), r#"{"id": 1}"#);
+        let op = spec.operation("getUser").unwrap();
+        assert_eq!(
+            spec.response_violations(&op, &wrong_body).unwrap(),
+            vec!["$: missing required property 'name'".to_string()]
+        );
+
+        let undeclared = Response::from_parts(StatusCode::INTERNAL_SERVER_ERROR, headers, "{}");
+        assert!(matches!(
+            undeclared.expect_conforms_to(&spec, "getUser"),
+            Err(Error::ContractViolation { violations, .. })
+                if violations[0].starts_with("status 500 is not declared")
+        ));
+    }
+
+    #[test]
+    fn test_media_type_matches() {
+        assert!(media_type_matches("application/json", "application/json"));
+        assert!(media_type_matches(
+            "application/*",
+            "application/problem+json"
+        ));
+        assert!(media_type_matches("*/*", "text/plain"));
+        assert!(!media_type_matches("application/json", "text/html"));
+    }
+
+    #[test]
+    fn test_validate() {
+        let spec = OpenApi::parse(SPEC).unwrap();
+        let schema = json!({"$ref": "#/components/schemas/User"});
+
+        assert!(spec
+            .validate(&schema, &json!({"id": 1, "name": "John", "email": null}))
+            .is_empty());
+
+        let errors = spec.validate(&schema, &json!({"id": "1"}));
+        assert_eq!(errors.len(), 2);
+        assert!(errors.contains(&"$: missing required property 'name'".to_string()));
+        assert!(errors.contains(&"$.id: expected integer, got string".to_string()));
+
+        let inlined = spec.inline_schema(&schema);
+        assert!(validate_schema(&inlined, &json!({"id": 1, "name": "John"})).is_empty());
+    }
+}
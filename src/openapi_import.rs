@@ -0,0 +1,317 @@
+use crate::config::{AssertionConfig, Config, RequestConfig};
+use std::collections::HashMap;
+use x_http::error::Result;
+use x_http::openapi::{OpenApi, OperationRef, Parameter, SecurityScheme};
+
+pub fn import(spec_path: &str, with_assertions: bool) -> Result<Config> {
+    let spec = OpenApi::from_file(spec_path)?;
+    from_spec(&spec, with_assertions)
+}
+
+pub fn from_spec(spec: &OpenApi, with_assertions: bool) -> Result<Config> {
+    let mut variables = HashMap::new();
+    variables.insert(
+        "baseUrl".to_string(),
+        spec.base_url().unwrap_or("http://localhost").to_string(),
+    );
+
+    let mut requests = Vec::new();
+    for op in spec.operations() {
+        requests.push(convert_operation(
+            spec,
+            &op,
+            with_assertions,
+            &mut variables,
+        )?);
+    }
+
+    Ok(Config {
+        variables,
+        requests,
+        ..Config::default()
+    })
+}
+
+fn convert_operation(
+    spec: &OpenApi,
+    op: &OperationRef<'_>,
+    with_assertions: bool,
+    variables: &mut HashMap<String, String>,
+) -> Result<RequestConfig> {
+    let mut url = format!(
+        "{{{{baseUrl}}}}{}",
+        op.path.replace('{', "{{").replace('}', "}}")
+    );
+    let mut headers = HashMap::new();
+    let mut query = Vec::new();
+
+    for parameter in spec.parameters(op)? {
+        match parameter.location.as_str() {
+            "path" => {}
+            "query" if parameter.required => {
+                query.push(format!("{}={{{{{}}}}}", parameter.name, parameter.name))
+            }
+            "header" if parameter.required => {
+                headers.insert(
+                    parameter.name.clone(),
+                    format!("{{{{{}}}}}", parameter.name),
+                );
+            }
+            _ => continue,
+        }
+        variables
+            .entry(parameter.name.clone())
+            .or_insert_with(|| parameter_example(spec, &parameter));
+    }
+
+    for (name, scheme) in spec.security_schemes(op) {
+        apply_security(name, scheme, &mut headers, &mut query, variables);
+    }
+
+    if !query.is_empty() {
+        url.push('?');
+        url.push_str(&query.join("&"));
+    }
+
+    let mut json = false;
+    let mut body = None;
+    if let Some(request_body) = spec.request_body(op)? {
+        let media = request_body
+            .content
+            .get_key_value("application/json")
+            .or_else(|| request_body.content.iter().next());
+
+        if let Some((content_type, media)) = media {
+            let example = media
+                .example
+                .clone()
+                .or_else(|| media.schema.as_ref().map(|s| spec.example_for(s)));
+
+            if let Some(example) = example {
+                json = content_type.contains("json");
+                body = Some(if json {
+                    serde_json::to_string_pretty(&example)?
+                } else {
+                    example
+                        .as_str()
+                        .map_or_else(|| example.to_string(), str::to_string)
+                });
+                headers.insert("Content-Type".to_string(), content_type.clone());
+            }
+        }
+    }
+
+    let assertions = if with_assertions {
+        response_assertions(spec, op)?
+    } else {
+        AssertionConfig::default()
+    };
+
+    Ok(RequestConfig {
+        name: operation_name(op),
+        method: op.method.as_str().to_string(),
+        url,
+        headers,
+        body,
+        json,
+        assertions,
+        ..RequestConfig::default()
+    })
+}
+
+fn operation_name(op: &OperationRef<'_>) -> String {
+    if let Some(id) = &op.operation.operation_id {
+        return id.clone();
+    }
+
+    let path: Vec<&str> = op
+        .path
+        .split('/')
+        .map(|segment| segment.trim_matches(|c| c == '{' || c == '}'))
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    format!("{}-{}", op.method.as_str().to_lowercase(), path.join("-"))
+}
+
+fn parameter_example(spec: &OpenApi, parameter: &Parameter) -> String {
+    let example = parameter
+        .example
+        .clone()
+        .or_else(|| parameter.schema.as_ref().map(|s| spec.example_for(s)));
+
+    match example {
+        Some(serde_json::Value::String(s)) => s,
+        Some(serde_json::Value::Null) | None => String::new(),
+        Some(other) => other.to_string(),
+    }
+}
+
+fn apply_security(
+    name: &str,
+    scheme: &SecurityScheme,
+    headers: &mut HashMap<String, String>,
+    query: &mut Vec<String>,
+    variables: &mut HashMap<String, String>,
+) {
+    let variable = match (scheme.scheme_type.as_str(), scheme.scheme.as_deref()) {
+        ("http", Some(s)) if s.eq_ignore_ascii_case("basic") => {
+            headers.insert(
+                "Authorization".to_string(),
+                format!("Basic {{{{{}}}}}", name),
+            );
+            name
+        }
+        ("http", _) | ("oauth2", _) | ("openIdConnect", _) => {
+            headers.insert(
+                "Authorization".to_string(),
+                format!("Bearer {{{{{}}}}}", name),
+            );
+            name
+        }
+        ("apiKey", _) => {
+            let key = scheme.name.clone().unwrap_or_else(|| name.to_string());
+            match scheme.location.as_deref() {
+                Some("query") => query.push(format!("{}={{{{{}}}}}", key, name)),
+                _ => {
+                    headers.insert(key, format!("{{{{{}}}}}", name));
+                }
+            }
+            name
+        }
+        _ => return,
+    };
+
+    variables.entry(variable.to_string()).or_default();
+}
+
+fn response_assertions(spec: &OpenApi, op: &OperationRef<'_>) -> Result<AssertionConfig> {
+    let mut assertions = AssertionConfig::default();
+
+    let Some(status) = op
+        .operation
+        .responses
+        .keys()
+        .find(|code| code.starts_with('2') && code.len() == 3)
+    else {
+        return Ok(assertions);
+    };
+
+    assertions.status = status.parse().ok();
+
+    if let Some(response) = spec.response(op, status)? {
+        assertions.schema = response
+            .content
+            .iter()
+            .find(|(content_type, _)| content_type.contains("json"))
+            .and_then(|(_, media)| media.schema.as_ref())
+            .map(|schema| spec.inline_schema(schema));
+    }
+
+    Ok(assertions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r##"
+openapi: 3.0.3
+info:
+  title: Pets
+  version: "1.0"
+servers:
+  - url: https://pets.example.com
+components:
+  securitySchemes:
+    apiKey:
+      type: apiKey
+      in: header
+      name: X-API-Key
+  schemas:
+    Pet:
+      type: object
+      required: [name]
+      properties:
+        id:
+          type: integer
+          example: 7
+        name:
+          type: string
+          example: Rex
+security:
+  - apiKey: []
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      parameters:
+        - name: limit
+          in: query
+          required: true
+          schema:
+            type: integer
+            default: 20
+      responses:
+        "200":
+          description: Pets
+          content:
+            application/json:
+              schema:
+                type: array
+                items:
+                  $ref: "#/components/schemas/Pet"
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema:
+              $ref: "#/components/schemas/Pet"
+      responses:
+        "201":
+          description: Created
+  /pets/{petId}:
+    delete:
+      parameters:
+        - name: petId
+          in: path
+          required: true
+          schema:
+            type: string
+      responses:
+        "204":
+          description: Deleted
+"##;
+
+    #[test]
+    fn test_import_openapi() {
+        let spec = OpenApi::parse(SPEC).unwrap();
+        let config = from_spec(&spec, true).unwrap();
+
+        assert_eq!(config.variables["baseUrl"], "https://pets.example.com");
+        assert_eq!(config.variables["limit"], "20");
+        assert_eq!(config.requests.len(), 3);
+
+        let list = &config.requests[0];
+        assert_eq!(list.name, "listPets");
+        assert_eq!(list.url, "{{baseUrl}}/pets?limit={{limit}}");
+        assert_eq!(list.headers["X-API-Key"], "{{apiKey}}");
+        assert_eq!(list.assertions.status, Some(200));
+        assert_eq!(
+            list.assertions.schema.as_ref().unwrap()["items"]["required"],
+            serde_json::json!(["name"])
+        );
+
+        let create = &config.requests[1];
+        assert_eq!(create.name, "post-pets");
+        assert!(create.json);
+        let body: serde_json::Value =
+            serde_json::from_str(create.body.as_deref().unwrap()).unwrap();
+        assert_eq!(body, serde_json::json!({"id": 7, "name": "Rex"}));
+
+        let delete = &config.requests[2];
+        assert_eq!(delete.name, "delete-pets-petId");
+        assert_eq!(delete.url, "{{baseUrl}}/pets/{{petId}}");
+        assert_eq!(delete.assertions.status, Some(204));
+    }
+}
@@ -0,0 +1,388 @@
+use crate::exit_status::ExitStatus;
+use clap::ValueEnum;
+use reqwest::header::HeaderMap;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::{self, Write as _};
+use std::time::Duration;
+use x_http::cassette::{DEFAULT_SCRUBBED_HEADERS, REDACTED};
+use x_http::error::{Error, Result};
+use x_http::{Request, Response};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Human,
+    Json,
+    Ndjson,
+    Junit,
+    Tap,
+}
+
+impl OutputFormat {
+    pub fn is_human(&self) -> bool {
+        *self == OutputFormat::Human
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AssertionOutcome {
+    pub description: String,
+    pub passed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl AssertionOutcome {
+    pub fn from_result<T, E: fmt::Display>(
+        description: impl Into<String>,
+        result: std::result::Result<T, E>,
+    ) -> Self {
+        let message = result.err().map(|e| e.to_string());
+        Self {
+            description: description.into(),
+            passed: message.is_none(),
+            message,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RequestResult {
+    pub name: String,
+    pub method: String,
+    pub url: String,
+    pub status: Option<u16>,
+    #[serde(rename = "duration_ms", serialize_with = "serialize_millis")]
+    pub duration: Duration,
+    pub request_headers: BTreeMap<String, String>,
+    pub request_body: Option<String>,
+    pub headers: BTreeMap<String, String>,
+    pub body: Option<String>,
+    pub assertions: Vec<AssertionOutcome>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip)]
+    pub failure: ExitStatus,
+}
+
+impl RequestResult {
+    pub fn new(name: &str, method: &str, url: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            method: method.to_uppercase(),
+            url: url.to_string(),
+            status: None,
+            duration: Duration::ZERO,
+            request_headers: BTreeMap::new(),
+            request_body: None,
+            headers: BTreeMap::new(),
+            body: None,
+            assertions: Vec::new(),
+            error: None,
+            failure: ExitStatus::Success,
+        }
+    }
+
+    pub fn record_error(&mut self, error: &Error) {
+        self.error = Some(error.to_string());
+        self.failure = ExitStatus::from_error(error);
+    }
+
+    pub fn record_request(&mut self, request: &Request) {
+        self.url = request
+            .full_url()
+            .map(String::from)
+            .unwrap_or_else(|_| request.url().to_string());
+        self.request_headers = header_strings(request.header_map());
+        self.request_body = request
+            .body_bytes()
+            .map(|body| String::from_utf8_lossy(body).into_owned());
+    }
+
+    pub fn record_response(&mut self, response: &Response) {
+        self.status = Some(response.status());
+        self.duration = response.duration();
+        self.headers = header_strings(response.headers());
+        self.body = Some(String::from_utf8_lossy(response.body_bytes()).into_owned());
+    }
+
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.assertions.iter().all(|a| a.passed)
+    }
+
+    pub fn exit_status(&self) -> ExitStatus {
+        if self.failure != ExitStatus::Success {
+            self.failure
+        } else if !self.passed() {
+            ExitStatus::AssertionFailed
+        } else {
+            ExitStatus::Success
+        }
+    }
+
+    pub fn failures(&self) -> Vec<String> {
+        self.error
+            .iter()
+            .cloned()
+            .chain(self.assertions.iter().filter(|a| !a.passed).map(|a| {
+                format!(
+                    "{}: {}",
+                    a.description,
+                    a.message.as_deref().unwrap_or("failed")
+                )
+            }))
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Summary {
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    #[serde(rename = "duration_ms", serialize_with = "serialize_millis")]
+    pub duration: Duration,
+}
+
+impl Summary {
+    pub fn from_results(results: &[RequestResult]) -> Self {
+        let passed = results.iter().filter(|r| r.passed()).count();
+        Self {
+            total: results.len(),
+            passed,
+            failed: results.len() - passed,
+            duration: results.iter().map(|r| r.duration).sum(),
+        }
+    }
+}
+
+fn header_strings(headers: &HeaderMap) -> BTreeMap<String, String> {
+    let mut strings = BTreeMap::new();
+    for (key, value) in headers {
+        let value = if DEFAULT_SCRUBBED_HEADERS.contains(&key.as_str()) {
+            REDACTED.into()
+        } else {
+            String::from_utf8_lossy(value.as_bytes())
+        };
+        strings
+            .entry(key.as_str().to_string())
+            .and_modify(|existing: &mut String| {
+                existing.push_str(", ");
+                existing.push_str(&value);
+            })
+            .or_insert_with(|| value.to_string());
+    }
+    strings
+}
+
+pub fn serialize_millis<S: serde::Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64() * 1000.0)
+}
+
+pub fn emit_result(result: &RequestResult, format: OutputFormat) -> Result<()> {
+    if format == OutputFormat::Ndjson {
+        println!("{}", serde_json::to_string(result)?);
+    }
+    Ok(())
+}
+
+pub fn emit_results(results: &[RequestResult], format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Human | OutputFormat::Ndjson => {}
+        OutputFormat::Json => {
+            let output = serde_json::json!({
+                "results": results,
+                "summary": Summary::from_results(results),
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        OutputFormat::Junit => print!("{}", junit(results)),
+        OutputFormat::Tap => print!("{}", tap(results)),
+    }
+    Ok(())
+}
+
+fn junit(results: &[RequestResult]) -> String {
+    let summary = Summary::from_results(results);
+    let errors = results.iter().filter(|r| r.error.is_some()).count();
+    let failures = summary.failed - errors;
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">",
+        summary.total,
+        failures,
+        errors,
+        summary.duration.as_secs_f64()
+    );
+    let _ = writeln!(
+        xml,
+        "  <testsuite name=\"x-http\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">",
+        summary.total,
+        failures,
+        errors,
+        summary.duration.as_secs_f64()
+    );
+
+    for result in results {
+        let _ = writeln!(
+            xml,
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\">",
+            xml_escape(&result.name),
+            xml_escape(&format!("{} {}", result.method, result.url)),
+            result.duration.as_secs_f64()
+        );
+
+        if let Some(error) = &result.error {
+            let _ = writeln!(xml, "      <error message=\"{}\"/>", xml_escape(error));
+        } else {
+            for assertion in result.assertions.iter().filter(|a| !a.passed) {
+                let _ = writeln!(
+                    xml,
+                    "      <failure message=\"{}\">{}</failure>",
+                    xml_escape(&assertion.description),
+                    xml_escape(assertion.message.as_deref().unwrap_or_default())
+                );
+            }
+        }
+
+        if let Some(status) = result.status {
+            let _ = writeln!(
+                xml,
+                "      <system-out>{}</system-out>",
+                xml_escape(&format!(
+                    "HTTP {}\n{}",
+                    status,
+                    result.body.as_deref().unwrap_or_default()
+                ))
+            );
+        }
+
+        xml.push_str("    </testcase>\n");
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+fn tap(results: &[RequestResult]) -> String {
+    let mut output = format!("TAP version 13\n1..{}\n", results.len());
+
+    for (index, result) in results.iter().enumerate() {
+        let status = if result.passed() { "ok" } else { "not ok" };
+        let _ = writeln!(output, "{} {} - {}", status, index + 1, result.name);
+
+        let failures = result.failures();
+        if !failures.is_empty() {
+            output.push_str("  ---\n");
+            let _ = writeln!(output, "  method: {}", result.method);
+            let _ = writeln!(output, "  url: {}", result.url);
+            if let Some(status) = result.status {
+                let _ = writeln!(output, "  status: {}", status);
+            }
+            output.push_str("  failures:\n");
+            for failure in failures {
+                let _ = writeln!(output, "    - {:?}", failure);
+            }
+            output.push_str("  ...\n");
+        }
+    }
+
+    output
+}
+
+pub fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c if c.is_control() && c != '\n' && c != '\t' && c != '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_results() -> Vec<RequestResult> {
+        let mut passed = RequestResult::new("list", "get", "https://example.com/users");
+        passed.status = Some(200);
+        passed.assertions.push(AssertionOutcome {
+            description: "status is 200".to_string(),
+            passed: true,
+            message: None,
+        });
+
+        let mut failed = RequestResult::new("create <user>", "POST", "https://example.com/users");
+        failed.status = Some(500);
+        failed.assertions.push(AssertionOutcome {
+            description: "status is 201".to_string(),
+            passed: false,
+            message: Some("Status code 201 expected, got 500".to_string()),
+        });
+
+        let mut errored = RequestResult::new("health", "GET", "http://localhost:1");
+        errored.error = Some("connection refused".to_string());
+
+        vec![passed, failed, errored]
+    }
+
+    #[test]
+    fn test_junit() {
+        let xml = junit(&sample_results());
+
+        assert!(xml.contains("<testsuite name=\"x-http\" tests=\"3\" failures=\"1\" errors=\"1\""));
+        assert!(xml.contains("<testcase name=\"create &lt;user&gt;\""));
+        assert!(xml.contains(
+            "<failure message=\"status is 201\">Status code 201 expected, got 500</failure>"
+        ));
+        assert!(xml.contains("<error message=\"connection refused\"/>"));
+    }
+
+    #[test]
+    fn test_tap() {
+        let tap = tap(&sample_results());
+
+        assert!(tap.starts_with("TAP version 13\n1..3\nok 1 - list\nnot ok 2 - create <user>\n"));
+        assert!(tap.contains("    - \"status is 201: Status code 201 expected, got 500\"\n"));
+        assert!(tap.contains("not ok 3 - health\n"));
+    }
+
+    #[test]
+    fn test_json_result() {
+        let value = serde_json::to_value(&sample_results()[0]).unwrap();
+
+        assert_eq!(value["method"], "GET");
+        assert_eq!(value["status"], 200);
+        assert_eq!(value["assertions"][0]["passed"], true);
+        assert!(value.get("error").is_none());
+    }
+
+    #[test]
+    fn test_record_request_redacts_secrets() {
+        let request = Request::get("https://example.com/users")
+            .query("page", "2")
+            .header("Authorization", "Bearer secret")
+            .header("Cookie", "session=abc")
+            .header("Accept", "application/json");
+        let mut result = RequestResult::new("list", "GET", request.url());
+        result.record_request(&request);
+
+        assert_eq!(result.url, "https://example.com/users?page=2");
+        assert_eq!(result.request_headers["authorization"], REDACTED);
+        assert_eq!(result.request_headers["cookie"], REDACTED);
+        assert_eq!(result.request_headers["accept"], "application/json");
+    }
+}
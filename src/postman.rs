@@ -0,0 +1,799 @@
+use crate::config::{AssertionConfig, BasicAuthConfig, Config, RequestConfig};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use x_http::error::{Error, Result};
+
+const SCHEMA_URL: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Collection {
+    pub info: Info,
+    #[serde(default)]
+    pub item: Vec<Item>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variable: Vec<KeyValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<Auth>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Info {
+    pub name: String,
+    #[serde(default)]
+    pub schema: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Item {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item: Option<Vec<Item>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<PostmanRequest>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub event: Vec<Event>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<Auth>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum PostmanRequest {
+    Url(String),
+    Full(Box<RequestDetails>),
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RequestDetails {
+    #[serde(default = "default_method")]
+    pub method: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub header: Vec<KeyValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Body>,
+    pub url: Url,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<Auth>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Url {
+    Raw(String),
+    Parts(Box<UrlParts>),
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UrlParts {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub query: Vec<KeyValue>,
+}
+
+impl Url {
+    fn to_url(&self) -> String {
+        let parts = match self {
+            Url::Raw(raw) => return raw.clone(),
+            Url::Parts(parts) => parts,
+        };
+        if let Some(raw) = &parts.raw {
+            return raw.clone();
+        }
+
+        let mut url = String::new();
+        if let Some(protocol) = &parts.protocol {
+            url.push_str(protocol);
+            url.push_str("://");
+        }
+        url.push_str(&join_segments(parts.host.as_ref(), "."));
+        if let Some(port) = &parts.port {
+            url.push(':');
+            url.push_str(port);
+        }
+        let path = join_segments(parts.path.as_ref(), "/");
+        if !path.is_empty() && !path.starts_with('/') {
+            url.push('/');
+        }
+        url.push_str(&path);
+
+        let query: Vec<String> = parts
+            .query
+            .iter()
+            .filter(|q| !q.disabled)
+            .map(|q| match q.value {
+                Value::Null => q.key.clone(),
+                _ => format!("{}={}", q.key, q.value_string()),
+            })
+            .collect();
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(&query.join("&"));
+        }
+        url
+    }
+}
+
+fn join_segments(value: Option<&Value>, separator: &str) -> String {
+    match value {
+        Some(Value::String(joined)) => joined.clone(),
+        Some(Value::Array(segments)) => segments
+            .iter()
+            .map(|segment| match segment {
+                Value::String(s) => s.clone(),
+                other => other
+                    .get("value")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(separator),
+        _ => String::new(),
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Body {
+    pub mode: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub urlencoded: Vec<KeyValue>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub formdata: Vec<FormParam>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<Value>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct FormParam {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub param_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub src: Option<Value>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
+}
+
+impl FormParam {
+    fn form_value(&self) -> Result<String> {
+        if self.param_type.as_deref() != Some("file") {
+            return Ok(self.value.clone().unwrap_or_default());
+        }
+        match &self.src {
+            Some(Value::String(path)) => Ok(format!("@{}", path)),
+            Some(Value::Array(paths)) if paths.len() == 1 && paths[0].is_string() => {
+                Ok(format!("@{}", paths[0].as_str().unwrap_or_default()))
+            }
+            _ => Err(Error::Config(format!(
+                "Postman form field '{}' must reference exactly one file",
+                self.key
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct KeyValue {
+    pub key: String,
+    #[serde(default)]
+    pub value: Value,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
+}
+
+impl KeyValue {
+    fn new(key: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            value: Value::String(value.into()),
+            disabled: false,
+        }
+    }
+
+    fn value_string(&self) -> String {
+        match &self.value {
+            Value::String(s) => s.clone(),
+            Value::Null => String::new(),
+            other => other.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Auth {
+    #[serde(rename = "type")]
+    pub auth_type: String,
+    #[serde(flatten)]
+    pub params: HashMap<String, Value>,
+}
+
+impl Auth {
+    fn param(&self, key: &str) -> Option<String> {
+        self.params
+            .get(&self.auth_type)?
+            .as_array()?
+            .iter()
+            .find(|entry| entry.get("key").and_then(Value::as_str) == Some(key))?
+            .get("value")
+            .map(|value| match value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+    }
+
+    fn basic(auth: &BasicAuthConfig) -> Self {
+        let param = |key: &str, value: &str| serde_json::json!({"key": key, "value": value, "type": "string"});
+        Self {
+            auth_type: "basic".to_string(),
+            params: HashMap::from([(
+                "basic".to_string(),
+                serde_json::json!([
+                    param("username", &auth.username),
+                    param("password", &auth.password)
+                ]),
+            )]),
+        }
+    }
+
+    fn apply(&self, request: &mut RequestConfig) {
+        let headers = &mut request.headers;
+        match self.auth_type.as_str() {
+            "bearer" => {
+                if let Some(token) = self.param("token") {
+                    headers.insert("Authorization".to_string(), format!("Bearer {}", token));
+                }
+            }
+            "basic" => {
+                request.basic_auth = Some(BasicAuthConfig {
+                    username: self.param("username").unwrap_or_default(),
+                    password: self.param("password").unwrap_or_default(),
+                });
+            }
+            "apikey" => {
+                let location = self.param("in").unwrap_or_else(|| "header".to_string());
+                if let (Some(key), Some(value), "header") =
+                    (self.param("key"), self.param("value"), location.as_str())
+                {
+                    headers.insert(key, value);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Event {
+    pub listen: String,
+    pub script: Script,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Script {
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub script_type: Option<String>,
+    #[serde(default)]
+    pub exec: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Environment {
+    #[serde(default)]
+    pub values: Vec<EnvironmentValue>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EnvironmentValue {
+    pub key: String,
+    #[serde(default)]
+    pub value: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+pub fn import(collection_path: &str, environment_path: Option<&str>) -> Result<Config> {
+    let contents = fs::read_to_string(collection_path)?;
+    let collection: Collection = serde_json::from_str(&contents)
+        .map_err(|e| Error::Config(format!("Failed to parse Postman collection: {}", e)))?;
+
+    let environment = environment_path
+        .map(|path| -> Result<Environment> {
+            let contents = fs::read_to_string(path)?;
+            serde_json::from_str(&contents)
+                .map_err(|e| Error::Config(format!("Failed to parse Postman environment: {}", e)))
+        })
+        .transpose()?;
+
+    from_collection(&collection, environment.as_ref())
+}
+
+pub fn from_collection(
+    collection: &Collection,
+    environment: Option<&Environment>,
+) -> Result<Config> {
+    let mut variables: HashMap<String, String> = collection
+        .variable
+        .iter()
+        .filter(|v| !v.disabled)
+        .map(|v| (v.key.clone(), v.value_string()))
+        .collect();
+
+    if let Some(environment) = environment {
+        for value in environment.values.iter().filter(|v| v.enabled) {
+            variables.insert(value.key.clone(), value.value.clone());
+        }
+    }
+
+    let mut requests = Vec::new();
+    collect_items(
+        &collection.item,
+        "",
+        collection.auth.as_ref(),
+        &mut requests,
+    )?;
+
+    Ok(Config {
+        variables,
+        requests,
+        ..Config::default()
+    })
+}
+
+fn collect_items(
+    items: &[Item],
+    prefix: &str,
+    inherited_auth: Option<&Auth>,
+    requests: &mut Vec<RequestConfig>,
+) -> Result<()> {
+    for item in items {
+        let name = if prefix.is_empty() {
+            item.name.clone()
+        } else {
+            format!("{}/{}", prefix, item.name)
+        };
+        let auth = item.auth.as_ref().or(inherited_auth);
+
+        if let Some(children) = &item.item {
+            collect_items(children, &name, auth, requests)?;
+        } else if let Some(request) = &item.request {
+            requests.push(convert_request(name, request, auth, &item.event)?);
+        }
+    }
+    Ok(())
+}
+
+fn convert_request(
+    name: String,
+    request: &PostmanRequest,
+    inherited_auth: Option<&Auth>,
+    events: &[Event],
+) -> Result<RequestConfig> {
+    let details = match request {
+        PostmanRequest::Url(url) => {
+            return Ok(RequestConfig {
+                name,
+                method: default_method(),
+                url: url.clone(),
+                headers: HashMap::new(),
+                assertions: parse_tests(events),
+                ..RequestConfig::default()
+            })
+        }
+        PostmanRequest::Full(details) => details,
+    };
+
+    let mut headers: HashMap<String, String> = details
+        .header
+        .iter()
+        .filter(|h| !h.disabled)
+        .map(|h| (h.key.clone(), h.value_string()))
+        .collect();
+
+    let mut json = false;
+    let body = details
+        .body
+        .as_ref()
+        .and_then(|body| match body.mode.as_str() {
+            "raw" => {
+                json = body
+                    .options
+                    .as_ref()
+                    .and_then(|o| o.pointer("/raw/language"))
+                    .and_then(Value::as_str)
+                    == Some("json")
+                    || headers.iter().any(|(k, v)| {
+                        k.eq_ignore_ascii_case("content-type") && v.contains("application/json")
+                    });
+                body.raw.clone()
+            }
+            "urlencoded" => {
+                headers
+                    .entry("Content-Type".to_string())
+                    .or_insert_with(|| "application/x-www-form-urlencoded".to_string());
+                let pairs: Vec<String> = body
+                    .urlencoded
+                    .iter()
+                    .filter(|p| !p.disabled)
+                    .map(|p| format!("{}={}", p.key, p.value_string()))
+                    .collect();
+                Some(pairs.join("&"))
+            }
+            _ => None,
+        });
+
+    let form = match details.body.as_ref() {
+        Some(body) if body.mode == "formdata" => body
+            .formdata
+            .iter()
+            .filter(|p| !p.disabled)
+            .map(|p| Ok((p.key.clone(), p.form_value()?)))
+            .collect::<Result<HashMap<_, _>>>()?,
+        _ => HashMap::new(),
+    };
+
+    let mut config = RequestConfig {
+        name,
+        method: details.method.to_uppercase(),
+        url: details.url.to_url(),
+        headers,
+        body,
+        json,
+        form,
+        assertions: parse_tests(events),
+        ..RequestConfig::default()
+    };
+    if let Some(auth) = details.auth.as_ref().or(inherited_auth) {
+        auth.apply(&mut config);
+    }
+    Ok(config)
+}
+
+fn parse_tests(events: &[Event]) -> AssertionConfig {
+    let mut assertions = AssertionConfig::default();
+
+    let lines = events
+        .iter()
+        .filter(|e| e.listen == "test")
+        .flat_map(|e| e.script.exec.iter());
+
+    for line in lines {
+        let line = line.trim();
+        if let Some(status) = call_argument(line, "pm.response.to.have.status(")
+            .or_else(|| call_argument(line, "pm.expect(pm.response.code).to.eql("))
+            .or_else(|| call_argument(line, "pm.expect(pm.response.code).to.equal("))
+            .and_then(|arg| arg.parse().ok())
+        {
+            assertions.status = Some(status);
+        } else if let Some(text) = call_argument(line, "pm.expect(pm.response.text()).to.include(")
+        {
+            assertions.body_contains = Some(unquote(text).to_string());
+        } else if let Some(args) = call_argument(line, "pm.response.to.have.header(") {
+            if let Some((key, value)) = args.split_once(',') {
+                assertions
+                    .headers
+                    .insert(unquote(key).to_string(), unquote(value).to_string());
+            }
+        }
+    }
+
+    assertions
+}
+
+fn call_argument<'a>(line: &'a str, prefix: &str) -> Option<&'a str> {
+    let start = line.find(prefix)? + prefix.len();
+    let end = line[start..].find(')')? + start;
+    Some(line[start..end].trim())
+}
+
+fn unquote(value: &str) -> &str {
+    value.trim().trim_matches(|c| c == '"' || c == '\'')
+}
+
+pub fn export(config: &Config, name: &str) -> Collection {
+    let mut variable: Vec<KeyValue> = config
+        .variables
+        .iter()
+        .map(|(key, value)| KeyValue::new(key, value))
+        .collect();
+    variable.sort_by(|a, b| a.key.cmp(&b.key));
+
+    let mut root = Folder::default();
+    for request in &config.requests {
+        let mut segments: Vec<&str> = request.name.split('/').collect();
+        let name = segments.pop().unwrap_or_default();
+        let folder = segments.into_iter().fold(&mut root, |folder, segment| {
+            folder.folders.entry(segment.to_string()).or_default()
+        });
+        folder.items.push(export_request(name, request));
+    }
+
+    Collection {
+        info: Info {
+            name: name.to_string(),
+            schema: SCHEMA_URL.to_string(),
+        },
+        item: root.into_items(),
+        variable,
+        auth: None,
+    }
+}
+
+#[derive(Default)]
+struct Folder {
+    folders: BTreeMap<String, Folder>,
+    items: Vec<Item>,
+}
+
+impl Folder {
+    fn into_items(self) -> Vec<Item> {
+        let mut items: Vec<Item> = self
+            .folders
+            .into_iter()
+            .map(|(name, folder)| Item {
+                name,
+                item: Some(folder.into_items()),
+                request: None,
+                event: Vec::new(),
+                auth: None,
+            })
+            .collect();
+        items.extend(self.items);
+        items
+    }
+}
+
+fn export_request(name: &str, request: &RequestConfig) -> Item {
+    let mut header: Vec<KeyValue> = request
+        .headers
+        .iter()
+        .map(|(key, value)| KeyValue::new(key, value))
+        .collect();
+    header.sort_by(|a, b| a.key.cmp(&b.key));
+
+    let mut body = request.body.as_ref().map(|raw| Body {
+        mode: "raw".to_string(),
+        raw: Some(raw.clone()),
+        urlencoded: Vec::new(),
+        formdata: Vec::new(),
+        options: request
+            .json
+            .then(|| serde_json::json!({"raw": {"language": "json"}})),
+    });
+    if body.is_none() && !request.form.is_empty() {
+        let mut formdata: Vec<FormParam> = request
+            .form
+            .iter()
+            .map(|(key, value)| match value.strip_prefix('@') {
+                Some(path) => FormParam {
+                    key: key.clone(),
+                    value: None,
+                    param_type: Some("file".to_string()),
+                    src: Some(Value::String(path.to_string())),
+                    disabled: false,
+                },
+                None => FormParam {
+                    key: key.clone(),
+                    value: Some(value.clone()),
+                    param_type: Some("text".to_string()),
+                    src: None,
+                    disabled: false,
+                },
+            })
+            .collect();
+        formdata.sort_by(|a, b| a.key.cmp(&b.key));
+        body = Some(Body {
+            mode: "formdata".to_string(),
+            raw: None,
+            urlencoded: Vec::new(),
+            formdata,
+            options: None,
+        });
+    }
+
+    let tests = export_tests(&request.assertions);
+    let event = if tests.is_empty() {
+        Vec::new()
+    } else {
+        vec![Event {
+            listen: "test".to_string(),
+            script: Script {
+                script_type: Some("text/javascript".to_string()),
+                exec: tests,
+            },
+        }]
+    };
+
+    Item {
+        name: name.to_string(),
+        item: None,
+        request: Some(PostmanRequest::Full(Box::new(RequestDetails {
+            method: request.method.to_uppercase(),
+            header,
+            body,
+            url: Url::Raw(request.url.clone()),
+            auth: request.basic_auth.as_ref().map(Auth::basic),
+        }))),
+        event,
+        auth: None,
+    }
+}
+
+fn export_tests(assertions: &AssertionConfig) -> Vec<String> {
+    let mut tests = Vec::new();
+
+    if let Some(status) = assertions.status {
+        tests.push(format!("pm.response.to.have.status({});", status));
+    }
+    for (key, value) in &assertions.headers {
+        tests.push(format!(
+            "pm.response.to.have.header({:?}, {:?});",
+            key, value
+        ));
+    }
+    for (path, value) in &assertions.fields {
+        tests.push(format!(
+            "pm.expect(pm.response.json().{}).to.eql({});",
+            path, value
+        ));
+    }
+    if let Some(text) = &assertions.body_contains {
+        tests.push(format!(
+            "pm.expect(pm.response.text()).to.include({:?});",
+            text
+        ));
+    }
+
+    tests
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLLECTION: &str = r#"{
+        "info": {"name": "Users", "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"},
+        "variable": [{"key": "baseUrl", "value": "https://api.example.com"}],
+        "auth": {"type": "bearer", "bearer": [{"key": "token", "value": "{{token}}", "type": "string"}]},
+        "item": [
+            {
+                "name": "Users",
+                "item": [
+                    {
+                        "name": "Create",
+                        "event": [{"listen": "test", "script": {"exec": ["pm.test(\"ok\", function () {", "    pm.response.to.have.status(201);", "});"]}}],
+                        "request": {
+                            "method": "POST",
+                            "header": [{"key": "Accept", "value": "application/json"}],
+                            "body": {"mode": "raw", "raw": "{\"name\": \"John\"}", "options": {"raw": {"language": "json"}}},
+                            "url": {"raw": "{{baseUrl}}/users", "host": ["{{baseUrl}}"], "path": ["users"]}
+                        }
+                    },
+                    {
+                        "name": "Admin",
+                        "item": [
+                            {
+                                "name": "Upload avatar",
+                                "request": {
+                                    "method": "POST",
+                                    "body": {"mode": "formdata", "formdata": [
+                                        {"key": "name", "value": "John", "type": "text"},
+                                        {"key": "avatar", "type": "file", "src": "me.png"},
+                                        {"key": "old", "value": "x", "disabled": true}
+                                    ]},
+                                    "url": {
+                                        "protocol": "https",
+                                        "host": ["api", "example", "com"],
+                                        "port": "8443",
+                                        "path": ["users", ":id", "avatar"],
+                                        "query": [{"key": "v", "value": "2"}, {"key": "draft", "value": null}, {"key": "off", "value": "1", "disabled": true}]
+                                    }
+                                }
+                            }
+                        ]
+                    }
+                ]
+            },
+            {
+                "name": "Health",
+                "request": {
+                    "method": "GET",
+                    "auth": {"type": "basic", "basic": [{"key": "username", "value": "{{user}}"}, {"key": "password", "value": "{{pass}}"}]},
+                    "url": "{{baseUrl}}/health"
+                }
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_import_collection() {
+        let collection: Collection = serde_json::from_str(COLLECTION).unwrap();
+        let config = from_collection(&collection, None).unwrap();
+
+        assert_eq!(config.variables["baseUrl"], "https://api.example.com");
+        assert_eq!(config.requests.len(), 3);
+
+        let create = &config.requests[0];
+        assert_eq!(create.name, "Users/Create");
+        assert_eq!(create.method, "POST");
+        assert_eq!(create.url, "{{baseUrl}}/users");
+        assert!(create.json);
+        assert_eq!(create.headers["Authorization"], "Bearer {{token}}");
+        assert_eq!(create.assertions.status, Some(201));
+
+        let upload = &config.requests[1];
+        assert_eq!(upload.name, "Users/Admin/Upload avatar");
+        assert_eq!(
+            upload.url,
+            "https://api.example.com:8443/users/:id/avatar?v=2&draft"
+        );
+        assert_eq!(upload.form.len(), 2);
+        assert_eq!(upload.form["name"], "John");
+        assert_eq!(upload.form["avatar"], "@me.png");
+
+        let health = &config.requests[2];
+        assert!(!health.headers.contains_key("Authorization"));
+        let auth = health.basic_auth.as_ref().unwrap();
+        assert_eq!(
+            (auth.username.as_str(), auth.password.as_str()),
+            ("{{user}}", "{{pass}}")
+        );
+
+        let variables = Config {
+            variables: HashMap::from([
+                ("baseUrl".to_string(), "https://api.example.com".to_string()),
+                ("user".to_string(), "user".to_string()),
+                ("pass".to_string(), "pass".to_string()),
+            ]),
+            ..Config::default()
+        };
+        let request = crate::config::build_request(&variables, health).unwrap();
+        assert_eq!(request.header_map()["Authorization"], "Basic dXNlcjpwYXNz");
+
+        let missing_file = COLLECTION.replace(r#""src": "me.png""#, r#""src": []"#);
+        let collection: Collection = serde_json::from_str(&missing_file).unwrap();
+        assert!(from_collection(&collection, None).is_err());
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let collection: Collection = serde_json::from_str(COLLECTION).unwrap();
+        let config = from_collection(&collection, None).unwrap();
+
+        let exported = export(&config, "Users");
+        let json = serde_json::to_string(&exported).unwrap();
+        let reimported = from_collection(&serde_json::from_str(&json).unwrap(), None).unwrap();
+
+        assert_eq!(exported.item.len(), 2);
+        assert_eq!(reimported.requests.len(), 3);
+        assert_eq!(reimported.requests[0].name, "Users/Admin/Upload avatar");
+        assert_eq!(reimported.requests[0].form["avatar"], "@me.png");
+        assert_eq!(reimported.requests[1].name, "Users/Create");
+        assert_eq!(reimported.requests[1].assertions.status, Some(201));
+        let auth = reimported.requests[2].basic_auth.as_ref().unwrap();
+        assert_eq!(auth.username, "{{user}}");
+        assert_eq!(reimported.variables["baseUrl"], "https://api.example.com");
+    }
+}
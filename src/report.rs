@@ -0,0 +1,216 @@
+use crate::output::{xml_escape as escape, RequestResult, Summary};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use x_http::error::Result;
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Roboto, sans-serif; margin: 2rem; color: #1f2933; background: #f5f7fa; }
+h1 { margin-bottom: 0.25rem; }
+.summary { display: flex; gap: 1rem; margin: 1.5rem 0; }
+.card { background: #fff; border-radius: 8px; padding: 1rem 1.5rem; box-shadow: 0 1px 3px rgba(0,0,0,0.1); }
+.card .value { font-size: 1.75rem; font-weight: bold; }
+.passed { color: #1a7f37; }
+.failed { color: #cf222e; }
+.chart { background: #fff; border-radius: 8px; padding: 1rem 1.5rem; margin-bottom: 1.5rem; box-shadow: 0 1px 3px rgba(0,0,0,0.1); }
+.bar-row { display: grid; grid-template-columns: 16rem 1fr 6rem; align-items: center; gap: 0.5rem; margin: 0.25rem 0; }
+.bar { height: 0.9rem; border-radius: 3px; background: #2f81f7; }
+.bar.fail { background: #cf222e; }
+.name { overflow: hidden; text-overflow: ellipsis; white-space: nowrap; }
+details { background: #fff; border-radius: 8px; margin-bottom: 0.75rem; box-shadow: 0 1px 3px rgba(0,0,0,0.1); }
+summary { cursor: pointer; padding: 0.75rem 1rem; font-weight: 600; }
+.content { padding: 0 1rem 1rem; }
+.badge { display: inline-block; min-width: 3rem; text-align: center; border-radius: 4px; padding: 0 0.4rem; margin-right: 0.5rem; color: #fff; }
+.badge.ok { background: #1a7f37; }
+.badge.ko { background: #cf222e; }
+pre { background: #0d1117; color: #e6edf3; padding: 0.75rem; border-radius: 6px; overflow-x: auto; max-height: 24rem; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 0.75rem; }
+td { border-bottom: 1px solid #d0d7de; padding: 0.25rem 0.5rem; font-family: monospace; vertical-align: top; }
+td:first-child { width: 18rem; color: #57606a; }
+ul.assertions { list-style: none; padding-left: 0; }
+"#;
+
+pub fn write_html(results: &[RequestResult], path: &str) -> Result<()> {
+    fs::write(path, render(results))?;
+    Ok(())
+}
+
+pub fn render(results: &[RequestResult]) -> String {
+    let summary = Summary::from_results(results);
+    let mut html = String::new();
+
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>x-http run report</title>\n");
+    let _ = writeln!(html, "<style>{}</style>\n</head>\n<body>", STYLE);
+    html.push_str("<h1>x-http run report</h1>\n");
+
+    let _ = writeln!(
+        html,
+        "<div class=\"summary\">\
+         <div class=\"card\"><div>Requests</div><div class=\"value\">{}</div></div>\
+         <div class=\"card\"><div>Passed</div><div class=\"value passed\">{}</div></div>\
+         <div class=\"card\"><div>Failed</div><div class=\"value failed\">{}</div></div>\
+         <div class=\"card\"><div>Total duration</div><div class=\"value\">{:.0} ms</div></div>\
+         </div>",
+        summary.total,
+        summary.passed,
+        summary.failed,
+        summary.duration.as_secs_f64() * 1000.0
+    );
+
+    render_timing_chart(&mut html, results);
+
+    html.push_str("<h2>Requests</h2>\n");
+    for result in results {
+        render_result(&mut html, result);
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn render_timing_chart(html: &mut String, results: &[RequestResult]) {
+    let slowest = results
+        .iter()
+        .map(|r| r.duration.as_secs_f64())
+        .fold(0.0, f64::max)
+        .max(f64::EPSILON);
+
+    html.push_str("<div class=\"chart\">\n<h2>Timing</h2>\n");
+    for result in results {
+        let millis = result.duration.as_secs_f64() * 1000.0;
+        let width = result.duration.as_secs_f64() / slowest * 100.0;
+        let _ = writeln!(
+            html,
+            "<div class=\"bar-row\"><span class=\"name\" title=\"{0}\">{0}</span>\
+             <div class=\"bar{1}\" style=\"width: {2:.1}%\"></div><span>{3:.1} ms</span></div>",
+            escape(&result.name),
+            if result.passed() { "" } else { " fail" },
+            width,
+            millis
+        );
+    }
+    html.push_str("</div>\n");
+}
+
+fn render_result(html: &mut String, result: &RequestResult) {
+    let passed = result.passed();
+    let status = result
+        .status
+        .map_or_else(|| "ERR".to_string(), |s| s.to_string());
+
+    let _ = writeln!(
+        html,
+        "<details{}>\n<summary><span class=\"badge {}\">{}</span>{} — {} {} ({:.1} ms)</summary>\n<div class=\"content\">",
+        if passed { "" } else { " open" },
+        if passed { "ok" } else { "ko" },
+        status,
+        escape(&result.name),
+        escape(&result.method),
+        escape(&result.url),
+        result.duration.as_secs_f64() * 1000.0
+    );
+
+    if let Some(error) = &result.error {
+        let _ = writeln!(html, "<p class=\"failed\">{}</p>", escape(error));
+    }
+
+    if !result.assertions.is_empty() {
+        html.push_str("<h3>Assertions</h3>\n<ul class=\"assertions\">\n");
+        for assertion in &result.assertions {
+            let _ = writeln!(
+                html,
+                "<li class=\"{}\">{} {}{}</li>",
+                if assertion.passed { "passed" } else { "failed" },
+                if assertion.passed { "✓" } else { "✗" },
+                escape(&assertion.description),
+                assertion
+                    .message
+                    .as_ref()
+                    .map(|m| format!(": {}", escape(m)))
+                    .unwrap_or_default()
+            );
+        }
+        html.push_str("</ul>\n");
+    }
+
+    render_message(
+        html,
+        "Request",
+        &result.request_headers,
+        result.request_body.as_deref(),
+    );
+    if result.status.is_some() {
+        render_message(html, "Response", &result.headers, result.body.as_deref());
+    }
+
+    html.push_str("</div>\n</details>\n");
+}
+
+fn render_message(
+    html: &mut String,
+    title: &str,
+    headers: &BTreeMap<String, String>,
+    body: Option<&str>,
+) {
+    let _ = writeln!(html, "<h3>{}</h3>", title);
+
+    if !headers.is_empty() {
+        html.push_str("<table>\n");
+        for (key, value) in headers {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td></tr>",
+                escape(key),
+                escape(value)
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    if let Some(body) = body.filter(|b| !b.is_empty()) {
+        let pretty = serde_json::from_str::<serde_json::Value>(body)
+            .ok()
+            .and_then(|json| serde_json::to_string_pretty(&json).ok())
+            .unwrap_or_else(|| body.to_string());
+        let _ = writeln!(html, "<pre>{}</pre>", escape(&pretty));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::AssertionOutcome;
+    use std::time::Duration;
+
+    #[test]
+    fn test_render_report() {
+        let mut ok = RequestResult::new("list <users>", "GET", "https://example.com/users");
+        ok.status = Some(200);
+        ok.duration = Duration::from_millis(40);
+        ok.body = Some(r#"{"users":[]}"#.to_string());
+
+        let mut failed = RequestResult::new("create", "POST", "https://example.com/users");
+        failed.status = Some(500);
+        failed.duration = Duration::from_millis(80);
+        failed
+            .request_headers
+            .insert("content-type".to_string(), "application/json".to_string());
+        failed.assertions.push(AssertionOutcome {
+            description: "status is 201".to_string(),
+            passed: false,
+            message: Some("Status code 201 expected, got 500".to_string()),
+        });
+
+        let html = render(&[ok, failed]);
+
+        assert!(html.contains("<div class=\"value passed\">1</div>"));
+        assert!(html.contains("<div class=\"value failed\">1</div>"));
+        assert!(html.contains("list &lt;users&gt;"));
+        assert!(html.contains("style=\"width: 50.0%\""));
+        assert!(html.contains("&quot;users&quot;: []"));
+        assert!(html.contains("<details open>"));
+        assert!(html.contains("status is 201: Status code 201 expected, got 500"));
+        assert!(!html.contains("<script"));
+    }
+}
@@ -1,10 +1,26 @@
-use crate::error::Result;
-use crate::response::Response;
-use reqwest::blocking::Client;
-use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use crate::compression::{Encoding, ACCEPT_ENCODING};
+use crate::cookie::CookieJar;
+use crate::error::{Error, Result};
+use crate::graphql::{self, Operation};
+use crate::response::{Redirect, RequestInfo, Response};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use bytes::Bytes;
+use reqwest::blocking::multipart::Form;
+use reqwest::blocking::{Body, Client, Response as ReqwestResponse};
+use reqwest::cookie::CookieStore;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_ENCODING, COOKIE};
 use serde::Serialize;
+use serde_json::Value;
 use std::collections::HashMap;
-use std::time::Duration;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+const MAX_REDIRECTS: usize = 10;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Method {
@@ -18,6 +34,18 @@ pub enum Method {
 }
 
 impl Method {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Delete => "DELETE",
+            Method::Patch => "PATCH",
+            Method::Head => "HEAD",
+            Method::Options => "OPTIONS",
+        }
+    }
+
     fn as_reqwest_method(&self) -> reqwest::Method {
         match self {
             Method::Get => reqwest::Method::GET,
@@ -31,15 +59,44 @@ impl Method {
     }
 }
 
-#[derive(Debug)]
+pub(crate) struct Exchange {
+    pub response: ReqwestResponse,
+    pub info: RequestInfo,
+    pub redirects: Vec<Redirect>,
+    pub started_at: SystemTime,
+    pub duration: Duration,
+}
+
+#[derive(Debug, Clone)]
+enum FormPart {
+    Text(String, String),
+    File(String, PathBuf),
+}
+
+type SharedReader = Arc<Mutex<Option<Box<dyn Read + Send>>>>;
+
+#[derive(Clone)]
+enum BodySource {
+    File(PathBuf),
+    Reader(SharedReader, Option<u64>),
+}
+
+#[derive(Debug, Clone)]
 pub struct Request {
     method: Method,
     url: String,
     headers: HeaderMap,
     body: Option<Vec<u8>>,
+    body_source: Option<BodySource>,
     query_params: HashMap<String, String>,
+    form: Vec<FormPart>,
     timeout: Option<Duration>,
     follow_redirects: bool,
+    accept_invalid_certs: bool,
+    max_body_size: Option<u64>,
+    compression: Option<Encoding>,
+    decompress: bool,
+    cookie_jar: Option<CookieJar>,
 }
 
 impl Request {
@@ -49,9 +106,16 @@ impl Request {
             url: url.into(),
             headers: HeaderMap::new(),
             body: None,
+            body_source: None,
             query_params: HashMap::new(),
+            form: Vec::new(),
             timeout: Some(Duration::from_secs(30)),
             follow_redirects: true,
+            accept_invalid_certs: false,
+            max_body_size: None,
+            compression: None,
+            decompress: true,
+            cookie_jar: None,
         }
     }
 
@@ -93,6 +157,14 @@ impl Request {
         self
     }
 
+    pub fn basic_auth(self, username: impl AsRef<str>, password: impl AsRef<str>) -> Self {
+        let credentials = format!("{}:{}", username.as_ref(), password.as_ref());
+        self.header(
+            "Authorization",
+            format!("Basic {}", STANDARD.encode(credentials)),
+        )
+    }
+
     pub fn headers(mut self, headers: Vec<(impl AsRef<str>, impl AsRef<str>)>) -> Self {
         for (key, value) in headers {
             self = self.header(key, value);
@@ -100,23 +172,97 @@ impl Request {
         self
     }
 
-    pub fn json<T: Serialize>(mut self, body: &T) -> Result<Self> {
+    pub fn json<T: Serialize>(self, body: &T) -> Result<Self> {
         let json_string = serde_json::to_string(body)?;
-        self.body = Some(json_string.into_bytes());
-        self = self.header("Content-Type", "application/json");
-        Ok(self)
+        Ok(self
+            .body(json_string)
+            .header("Content-Type", "application/json"))
+    }
+
+    pub fn graphql(
+        self,
+        query: &str,
+        variables: Option<&Value>,
+        operation_name: Option<&str>,
+    ) -> Result<Self> {
+        self.json(&Operation {
+            query,
+            variables,
+            operation_name,
+        })
+    }
+
+    pub fn graphql_file(
+        self,
+        path: impl AsRef<Path>,
+        variables: Option<&Value>,
+        operation_name: Option<&str>,
+    ) -> Result<Self> {
+        let query = graphql::load_query(path)?;
+        self.graphql(&query, variables, operation_name)
     }
 
     pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
         self.body = Some(body.into());
+        self.body_source = None;
+        self
+    }
+
+    pub fn body_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.body = None;
+        self.body_source = Some(BodySource::File(path.into()));
+        self
+    }
+
+    pub fn body_reader(self, reader: impl Read + Send + 'static) -> Self {
+        self.stream_body(Box::new(reader), None)
+    }
+
+    pub fn body_reader_sized(self, reader: impl Read + Send + 'static, length: u64) -> Self {
+        self.stream_body(Box::new(reader), Some(length))
+    }
+
+    fn stream_body(mut self, reader: Box<dyn Read + Send>, length: Option<u64>) -> Self {
+        self.body = None;
+        self.body_source = Some(BodySource::Reader(
+            Arc::new(Mutex::new(Some(reader))),
+            length,
+        ));
         self
     }
 
+    pub(crate) fn buffer_body(mut self) -> Result<Self> {
+        let Some(source) = self.body_source.take() else {
+            return Ok(self);
+        };
+        let (mut reader, _) = source.into_reader()?;
+        let mut body = Vec::new();
+        reader.read_to_end(&mut body)?;
+        self.body = Some(body);
+        Ok(self)
+    }
+
     pub fn text(self, text: impl Into<String>) -> Self {
         self.body(text.into().into_bytes())
             .header("Content-Type", "text/plain")
     }
 
+    pub fn soap(self, action: &str, envelope: impl Into<String>) -> Self {
+        self.body(envelope.into().into_bytes())
+            .header("Content-Type", "text/xml; charset=utf-8")
+            .header("SOAPAction", format!("\"{}\"", action.trim_matches('"')))
+    }
+
+    pub fn form_text(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.form.push(FormPart::Text(name.into(), value.into()));
+        self
+    }
+
+    pub fn form_file(mut self, name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        self.form.push(FormPart::File(name.into(), path.into()));
+        self
+    }
+
     pub fn query(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
         self.query_params.insert(key.into(), value.into());
         self
@@ -137,22 +283,175 @@ impl Request {
         self
     }
 
-    pub fn send(self) -> Result<Response> {
-        let client = Client::builder()
-            .redirect(if self.follow_redirects {
-                reqwest::redirect::Policy::default()
-            } else {
-                reqwest::redirect::Policy::none()
-            })
-            .build()?;
+    pub fn max_body_size(mut self, bytes: u64) -> Self {
+        self.max_body_size = Some(bytes);
+        self
+    }
+
+    pub fn compress_body(mut self, encoding: Encoding) -> Self {
+        self.compression = Some(encoding);
+        self
+    }
+
+    pub fn accept_compressed(self) -> Self {
+        self.header("Accept-Encoding", ACCEPT_ENCODING)
+    }
+
+    pub fn decompress(mut self, decompress: bool) -> Self {
+        self.decompress = decompress;
+        self
+    }
+
+    pub fn cookie_jar(mut self, jar: CookieJar) -> Self {
+        self.cookie_jar = Some(jar);
+        self
+    }
+
+    pub fn method(&self) -> Method {
+        self.method
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn header_map(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    pub fn body_bytes(&self) -> Option<&[u8]> {
+        self.body.as_deref()
+    }
+
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.accept_invalid_certs = accept;
+        self
+    }
+
+    pub(crate) fn body_limit(&self) -> Option<u64> {
+        self.max_body_size
+    }
+
+    pub(crate) fn decompresses(&self) -> bool {
+        self.decompress
+    }
 
+    pub fn full_url(&self) -> Result<url::Url> {
         let mut url = url::Url::parse(&self.url)?;
 
-        for (key, value) in self.query_params {
-            url.query_pairs_mut().append_pair(&key, &value);
+        for (key, value) in &self.query_params {
+            url.query_pairs_mut().append_pair(key, value);
+        }
+
+        Ok(url)
+    }
+
+    pub fn to_curl(&self) -> String {
+        let mut parts = vec!["curl".to_string()];
+
+        if self.method != Method::Get || self.body.is_some() || self.body_source.is_some() {
+            parts.push(format!("-X {}", self.method.as_str()));
+        }
+
+        let url = self
+            .full_url()
+            .map(String::from)
+            .unwrap_or_else(|_| self.url.clone());
+        parts.push(shell_quote(&url));
+
+        for (key, value) in &self.headers {
+            let value = String::from_utf8_lossy(value.as_bytes());
+            parts.push(format!(
+                "-H {}",
+                shell_quote(&format!("{}: {}", key, value))
+            ));
+        }
+
+        if let Some(body) = &self.body {
+            let body = String::from_utf8_lossy(body);
+            parts.push(format!("--data-raw {}", shell_quote(&body)));
+        }
+
+        match &self.body_source {
+            Some(BodySource::File(path)) => parts.push(format!(
+                "--data-binary {}",
+                shell_quote(&format!("@{}", path.display()))
+            )),
+            Some(BodySource::Reader(..)) => parts.push("--data-binary @-".to_string()),
+            None => {}
+        }
+
+        for part in &self.form {
+            let field = match part {
+                FormPart::Text(name, value) => format!("{}={}", name, value),
+                FormPart::File(name, path) => format!("{}=@{}", name, path.display()),
+            };
+            parts.push(format!("-F {}", shell_quote(&field)));
+        }
+
+        if let Some(timeout) = self.timeout {
+            parts.push(format!("--max-time {}", timeout.as_secs_f64()));
+        }
+
+        if self.follow_redirects {
+            parts.push("-L".to_string());
+        }
+
+        if self.accept_invalid_certs {
+            parts.push("-k".to_string());
+        }
+
+        parts.join(" ")
+    }
+
+    pub fn send(self) -> Result<Response> {
+        self.send_streaming()?.into_response()
+    }
+
+    pub fn send_with(self, client: &HttpClient) -> Result<Response> {
+        self.send_streaming_with(client)?.into_response()
+    }
+
+    pub(crate) fn dispatch(self) -> Result<Exchange> {
+        let client = HttpClient::new(&self)?;
+        self.dispatch_with(&client)
+    }
+
+    pub(crate) fn dispatch_with(mut self, client: &HttpClient) -> Result<Exchange> {
+        if self.compression.is_some() && !self.form.is_empty() {
+            return Err(Error::Config(
+                "compress_body cannot be combined with multipart form fields".to_string(),
+            ));
+        }
+        if let Some(encoding) = self.compression {
+            self.headers.insert(
+                CONTENT_ENCODING,
+                HeaderValue::from_static(encoding.as_str()),
+            );
+            if let Some(body) = self.body.take() {
+                self.body = Some(encoding.encode(&body)?);
+            }
+        }
+
+        let body = self.body.take().map(Bytes::from);
+        let url = self.full_url()?;
+        let mut info = RequestInfo {
+            method: self.method.as_str().to_string(),
+            url: url.to_string(),
+            headers: self.headers.clone(),
+            body: body.clone(),
+        };
+
+        if let Some(jar) = &self.cookie_jar {
+            if !info.headers.contains_key(COOKIE) {
+                if let Some(cookies) = CookieStore::cookies(jar, &url) {
+                    info.headers.insert(COOKIE, cookies);
+                }
+            }
         }
 
         let mut request_builder = client
+            .client
             .request(self.method.as_reqwest_method(), url)
             .headers(self.headers);
 
@@ -160,18 +459,140 @@ impl Request {
             request_builder = request_builder.timeout(timeout);
         }
 
-        if let Some(body) = self.body {
+        if let Some(body) = body {
             request_builder = request_builder.body(body);
         }
 
-        let start = std::time::Instant::now();
+        if let Some(source) = self.body_source {
+            request_builder = request_builder.body(source.into_body(self.compression)?);
+        }
+
+        if !self.form.is_empty() {
+            let mut form = Form::new();
+            for part in self.form {
+                form = match part {
+                    FormPart::Text(name, value) => form.text(name, value),
+                    FormPart::File(name, path) => form.file(name, path)?,
+                };
+            }
+            request_builder = request_builder.multipart(form);
+        }
+
+        client.take_redirects();
+        let started_at = SystemTime::now();
+        let start = Instant::now();
         let response = request_builder.send()?;
         let duration = start.elapsed();
 
-        Response::from_reqwest(response, duration)
+        Ok(Exchange {
+            response,
+            info,
+            redirects: client.take_redirects(),
+            started_at,
+            duration,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct HttpClient {
+    client: Client,
+    redirects: Arc<Mutex<Vec<Redirect>>>,
+}
+
+impl HttpClient {
+    pub fn new(request: &Request) -> Result<Self> {
+        let redirects = Arc::new(Mutex::new(Vec::new()));
+        let policy = if request.follow_redirects {
+            let redirects = Arc::clone(&redirects);
+            reqwest::redirect::Policy::custom(move |attempt| {
+                if attempt.previous().len() > MAX_REDIRECTS {
+                    return attempt.error("too many redirects");
+                }
+                if let Some(from) = attempt.previous().last() {
+                    redirects
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .push(Redirect {
+                            url: from.to_string(),
+                            status: attempt.status().as_u16(),
+                            location: attempt.url().to_string(),
+                        });
+                }
+                attempt.follow()
+            })
+        } else {
+            reqwest::redirect::Policy::none()
+        };
+
+        let mut client = Client::builder()
+            .redirect(policy)
+            .danger_accept_invalid_certs(request.accept_invalid_certs);
+        if let Some(jar) = &request.cookie_jar {
+            client = client.cookie_provider(Arc::new(jar.clone()));
+        }
+
+        Ok(Self {
+            client: client.build()?,
+            redirects,
+        })
+    }
+
+    fn take_redirects(&self) -> Vec<Redirect> {
+        std::mem::take(&mut *self.redirects.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+impl BodySource {
+    fn into_reader(self) -> Result<(Box<dyn Read + Send>, Option<u64>)> {
+        Ok(match self {
+            BodySource::File(path) => {
+                let file = File::open(path)?;
+                let length = file.metadata()?.len();
+                (Box::new(file), Some(length))
+            }
+            BodySource::Reader(reader, length) => {
+                let reader = reader
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .take()
+                    .ok_or_else(|| io::Error::other("request body reader was already consumed"))?;
+                (reader, length)
+            }
+        })
+    }
+
+    fn into_body(self, compression: Option<Encoding>) -> Result<Body> {
+        let (reader, length) = self.into_reader()?;
+        Ok(match (compression, length) {
+            (Some(encoding), _) => Body::new(encoding.encoder(reader)?),
+            (None, Some(length)) => Body::sized(reader, length),
+            (None, None) => Body::new(reader),
+        })
     }
 }
 
+impl fmt::Debug for BodySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BodySource::File(path) => f.debug_tuple("File").field(path).finish(),
+            BodySource::Reader(_, length) => f.debug_tuple("Reader").field(length).finish(),
+        }
+    }
+}
+
+fn shell_quote(value: &str) -> String {
+    if !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c))
+    {
+        return value.to_string();
+    }
+
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -202,4 +623,71 @@ mod tests {
         assert!(req.body.is_some());
         assert!(req.headers.contains_key("content-type"));
     }
+
+    #[test]
+    fn test_to_curl() {
+        let req = Request::post("https://example.com/users")
+            .query("page", "1")
+            .header("Authorization", "Bearer token")
+            .text("it's here")
+            .timeout(Duration::from_secs(5))
+            .follow_redirects(false);
+
+        assert_eq!(
+            req.to_curl(),
+            "curl -X POST 'https://example.com/users?page=1' \
+             -H 'authorization: Bearer token' -H 'content-type: text/plain' \
+             --data-raw 'it'\\''s here' --max-time 5"
+        );
+    }
+
+    #[test]
+    fn test_streaming_bodies() {
+        use crate::mock::{MockServer, Stub};
+
+        let server = MockServer::start().unwrap();
+        server.mock(Stub::post("/upload").status(201));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("payload.bin");
+        std::fs::write(&path, "file payload").unwrap();
+
+        let req = Request::post(server.url("/upload")).body_file(&path);
+        assert!(req
+            .to_curl()
+            .contains(&format!("--data-binary @{}", path.display())));
+        req.send().unwrap().expect_status(201).unwrap();
+
+        Request::put(server.url("/upload"))
+            .body("ignored")
+            .body_reader("chunked payload".as_bytes())
+            .send()
+            .unwrap();
+        Request::post(server.url("/upload"))
+            .body_reader_sized("sized".as_bytes(), 5)
+            .send()
+            .unwrap();
+
+        let received = server.received_requests();
+        assert_eq!(received[0].body, b"file payload");
+        assert_eq!(received[0].headers.get("content-length").unwrap(), "12");
+        assert_eq!(received[1].body, b"chunked payload");
+        assert_eq!(
+            received[1].headers.get("transfer-encoding").unwrap(),
+            "chunked"
+        );
+        assert_eq!(received[2].body, b"sized");
+
+        let req = Request::post(server.url("/upload")).body_reader(io::empty());
+        req.clone().send().unwrap();
+        assert!(req
+            .send()
+            .unwrap_err()
+            .to_string()
+            .contains("already consumed"));
+        assert!(Request::post(server.url("/upload"))
+            .body_file(dir.path().join("missing"))
+            .send()
+            .is_err());
+    }
 }
@@ -1,33 +1,143 @@
+use crate::compression;
+use crate::cookie::Cookie;
 use crate::error::{Error, Result};
+use crate::graphql;
+use crate::html::HtmlDocument;
+use crate::openapi::OpenApi;
+use crate::snapshot::Snapshot;
+use crate::stream::read_limited;
+use crate::xml::XmlDocument;
+use bytes::Bytes;
+use encoding_rs::{Encoding as Charset, UTF_8};
 use reqwest::blocking::Response as ReqwestResponse;
-use reqwest::header::HeaderMap;
+use reqwest::header::{HeaderMap, CONTENT_ENCODING, SET_COOKIE};
 use reqwest::StatusCode;
 use serde::de::DeserializeOwned;
 use serde_json::Value;
-use std::time::Duration;
+use std::borrow::Cow;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+const SNIFF_LENGTH: usize = 1024;
+
+#[derive(Debug, Clone, Default)]
+pub struct RequestInfo {
+    pub method: String,
+    pub url: String,
+    pub headers: HeaderMap,
+    pub body: Option<Bytes>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redirect {
+    pub url: String,
+    pub status: u16,
+    pub location: String,
+}
 
 #[derive(Debug)]
 pub struct Response {
     status: StatusCode,
     headers: HeaderMap,
     body: Vec<u8>,
+    encoded_size: usize,
+    decoded_size: usize,
     duration: Duration,
+    url: String,
+    request: RequestInfo,
+    redirects: Vec<Redirect>,
+    started_at: SystemTime,
+    xml_namespaces: Vec<(String, String)>,
 }
 
 impl Response {
-    pub(crate) fn from_reqwest(response: ReqwestResponse, duration: Duration) -> Result<Self> {
+    pub(crate) fn from_reqwest(
+        response: ReqwestResponse,
+        duration: Duration,
+        max_body_size: Option<u64>,
+        decompress: bool,
+    ) -> Result<Self> {
         let status = response.status();
         let headers = response.headers().clone();
-        let body = response.bytes()?.to_vec();
+        let url = response.url().to_string();
+        let body = match max_body_size {
+            Some(limit) => {
+                let content_length = response.content_length();
+                read_limited(response, content_length, limit)?
+            }
+            None => response.bytes()?.to_vec(),
+        };
+
+        let encoded_size = body.len();
+        let content_encoding = headers
+            .get(CONTENT_ENCODING)
+            .and_then(|value| value.to_str().ok());
+        let decoded = match content_encoding {
+            Some(encoding) if decompress => compression::decode(encoding, &body, max_body_size)?,
+            Some(encoding) => match compression::decode(encoding, &body, max_body_size) {
+                Err(e @ Error::BodyTooLarge { .. }) => return Err(e),
+                decoded => decoded.ok().flatten(),
+            },
+            None => None,
+        };
+        let (body, decoded_size) = match decoded {
+            Some(decoded) if decompress => {
+                let size = decoded.len();
+                (decoded, size)
+            }
+            Some(decoded) => (body, decoded.len()),
+            None => (body, encoded_size),
+        };
 
         Ok(Self {
             status,
             headers,
             body,
+            encoded_size,
+            decoded_size,
             duration,
+            url,
+            request: RequestInfo::default(),
+            redirects: Vec::new(),
+            started_at: SystemTime::now(),
+            xml_namespaces: Vec::new(),
         })
     }
 
+    pub(crate) fn with_exchange(
+        mut self,
+        request: RequestInfo,
+        redirects: Vec<Redirect>,
+        started_at: SystemTime,
+    ) -> Self {
+        self.request = request;
+        self.redirects = redirects;
+        self.started_at = started_at;
+        self
+    }
+
+    pub(crate) fn from_parts(
+        status: StatusCode,
+        headers: HeaderMap,
+        body: impl Into<Vec<u8>>,
+    ) -> Self {
+        let body = body.into();
+        Self {
+            status,
+            headers,
+            encoded_size: body.len(),
+            decoded_size: body.len(),
+            body,
+            duration: Duration::ZERO,
+            url: String::new(),
+            request: RequestInfo::default(),
+            redirects: Vec::new(),
+            started_at: SystemTime::now(),
+            xml_namespaces: Vec::new(),
+        }
+    }
+
     pub fn status(&self) -> u16 {
         self.status.as_u16()
     }
@@ -52,22 +162,129 @@ impl Response {
         self.headers.get(key)?.to_str().ok()
     }
 
+    pub fn cookies(&self) -> Vec<Cookie> {
+        self.headers
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(Cookie::parse)
+            .collect()
+    }
+
+    pub fn cookie(&self, name: &str) -> Option<Cookie> {
+        self.cookies()
+            .into_iter()
+            .rev()
+            .find(|cookie| cookie.name == name)
+    }
+
     pub fn duration(&self) -> Duration {
         self.duration
     }
 
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn request(&self) -> &RequestInfo {
+        &self.request
+    }
+
+    pub fn redirects(&self) -> &[Redirect] {
+        &self.redirects
+    }
+
+    pub fn started_at(&self) -> SystemTime {
+        self.started_at
+    }
+
     pub fn body_bytes(&self) -> &[u8] {
         &self.body
     }
 
-    pub fn text(&self) -> Result<String> {
-        String::from_utf8(self.body.clone())
-            .map_err(|e| Error::Assertion(format!("Response body is not valid UTF-8: {}", e)))
+    pub fn content_encoding(&self) -> Option<&str> {
+        self.header("content-encoding")
+    }
+
+    pub fn encoded_size(&self) -> usize {
+        self.encoded_size
+    }
+
+    pub fn decoded_size(&self) -> usize {
+        self.decoded_size
+    }
+
+    pub fn charset(&self) -> &'static str {
+        self.charset_encoding().name()
+    }
+
+    pub fn text(&self) -> Result<Cow<'_, str>> {
+        let charset = self.charset_encoding();
+        if charset == UTF_8 {
+            let body = self
+                .body
+                .strip_prefix(b"\xEF\xBB\xBF")
+                .unwrap_or(&self.body);
+            return std::str::from_utf8(body)
+                .map(Cow::Borrowed)
+                .map_err(|e| Error::Assertion(format!("Response body is not valid UTF-8: {}", e)));
+        }
+
+        let body = match Charset::for_bom(&self.body) {
+            Some((bom_charset, length)) if bom_charset == charset => &self.body[length..],
+            _ => &self.body,
+        };
+        charset
+            .decode_without_bom_handling_and_without_replacement(body)
+            .ok_or_else(|| {
+                Error::Assertion(format!("Response body is not valid {}", charset.name()))
+            })
     }
 
     pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
-        let text = self.text()?;
-        serde_json::from_str(&text).map_err(|e| e.into())
+        if self.charset_encoding() == UTF_8 {
+            let body = self
+                .body
+                .strip_prefix(b"\xEF\xBB\xBF")
+                .unwrap_or(&self.body);
+            return serde_json::from_slice(body).map_err(|e| e.into());
+        }
+        serde_json::from_str(&self.text()?).map_err(|e| e.into())
+    }
+
+    pub fn xml(&self) -> Result<XmlDocument> {
+        let document = XmlDocument::parse(&self.text()?)?;
+        Ok(self
+            .xml_namespaces
+            .iter()
+            .fold(document, |document, (prefix, uri)| {
+                document.namespace(prefix, uri)
+            }))
+    }
+
+    pub fn html(&self) -> Result<HtmlDocument> {
+        Ok(HtmlDocument::parse(&self.text()?))
+    }
+
+    pub fn xml_namespace(mut self, prefix: impl Into<String>, uri: impl Into<String>) -> Self {
+        self.xml_namespaces.push((prefix.into(), uri.into()));
+        self
+    }
+
+    pub fn save_body(&self, path: impl AsRef<Path>) -> Result<()> {
+        fs::write(path, &self.body)?;
+        Ok(())
+    }
+
+    fn charset_encoding(&self) -> &'static Charset {
+        let declared = self
+            .header("content-type")
+            .and_then(|content_type| parameter(content_type, "charset"))
+            .and_then(|label| Charset::for_label(label.as_bytes()));
+        declared
+            .or_else(|| Charset::for_bom(&self.body).map(|(charset, _)| charset))
+            .or_else(|| sniff_charset(&self.body[..self.body.len().min(SNIFF_LENGTH)]))
+            .unwrap_or(UTF_8)
     }
 
     pub fn json_value(&self) -> Result<Value> {
@@ -76,34 +293,168 @@ impl Response {
 
     // Assertion methods - chainable
     pub fn expect_status(self, expected: u16) -> Result<Self> {
+        self.check_status(expected)?;
+        Ok(self)
+    }
+
+    pub fn expect_success(self) -> Result<Self> {
+        self.check_success()?;
+        Ok(self)
+    }
+
+    pub fn expect_error(self) -> Result<Self> {
+        self.check_error()?;
+        Ok(self)
+    }
+
+    pub fn expect_json(self) -> Result<Self> {
+        self.check_json()?;
+        Ok(self)
+    }
+
+    pub fn expect_text(self) -> Result<Self> {
+        self.text()?;
+        Ok(self)
+    }
+
+    pub fn expect_xml(self) -> Result<Self> {
+        self.check_xml()?;
+        Ok(self)
+    }
+
+    pub fn assert_xpath(self, xpath: &str, expected: &str) -> Result<Self> {
+        self.xml()?.check_xpath(xpath, expected)?;
+        Ok(self)
+    }
+
+    pub fn assert_xpath_count(self, xpath: &str, expected: usize) -> Result<Self> {
+        self.xml()?.check_xpath_count(xpath, expected)?;
+        Ok(self)
+    }
+
+    pub fn assert_selector_exists(self, css: &str) -> Result<Self> {
+        self.html()?.check_selector_exists(css)?;
+        Ok(self)
+    }
+
+    pub fn assert_selector_text(self, css: &str, expected: &str) -> Result<Self> {
+        self.html()?.check_selector_text(css, expected)?;
+        Ok(self)
+    }
+
+    pub fn assert_selector_count(self, css: &str, expected: usize) -> Result<Self> {
+        self.html()?.check_selector_count(css, expected)?;
+        Ok(self)
+    }
+
+    pub fn assert_attribute(self, css: &str, attribute: &str, expected: &str) -> Result<Self> {
+        self.html()?.check_attribute(css, attribute, expected)?;
+        Ok(self)
+    }
+
+    pub fn expect_compressed(self, encoding: &str) -> Result<Self> {
+        self.check_compressed(encoding)?;
+        Ok(self)
+    }
+
+    pub fn expect_body_contains(self, text: &str) -> Result<Self> {
+        self.check_body_contains(text)?;
+        Ok(self)
+    }
+
+    pub fn expect_header(self, key: &str, expected: &str) -> Result<Self> {
+        self.check_header(key, expected)?;
+        Ok(self)
+    }
+
+    pub fn expect_cookie(self, name: &str, expected: &str) -> Result<Self> {
+        self.check_cookie(name, expected)?;
+        Ok(self)
+    }
+
+    pub fn expect_cookie_flags(self, name: &str, flags: &str) -> Result<Self> {
+        self.check_cookie_flags(name, flags)?;
+        Ok(self)
+    }
+
+    pub fn expect_content_type(self, content_type: &str) -> Result<Self> {
+        self.expect_header("content-type", content_type)
+    }
+
+    pub fn assert_field(self, path: &str, expected: impl Into<Value>) -> Result<Self> {
+        self.check_field(path, &expected.into())?;
+        Ok(self)
+    }
+
+    pub fn assert_field_exists(self, path: &str) -> Result<Self> {
+        self.check_field_exists(path)?;
+        Ok(self)
+    }
+
+    pub fn expect_no_graphql_errors(self) -> Result<Self> {
+        self.check_no_graphql_errors()?;
+        Ok(self)
+    }
+
+    pub fn assert_data_field(self, path: &str, expected: impl Into<Value>) -> Result<Self> {
+        self.check_data_field(path, &expected.into())?;
+        Ok(self)
+    }
+
+    pub fn expect_schema(self, schema: &Value) -> Result<Self> {
+        self.check_schema(schema)?;
+        Ok(self)
+    }
+
+    pub fn expect_snapshot(self, name: &str) -> Result<Self> {
+        self.expect_snapshot_with(name, &Snapshot::new())
+    }
+
+    pub fn expect_snapshot_with(self, name: &str, snapshot: &Snapshot) -> Result<Self> {
+        snapshot.assert(name, &self)?;
+        Ok(self)
+    }
+
+    pub fn expect_conforms_to(self, spec: &OpenApi, operation: &str) -> Result<Self> {
+        self.check_conforms_to(spec, operation)?;
+        Ok(self)
+    }
+
+    pub fn assert_array_length(self, path: &str, expected_length: usize) -> Result<Self> {
+        self.check_array_length(path, expected_length)?;
+        Ok(self)
+    }
+
+    // Borrowing checks behind the chainable assertions
+    pub fn check_status(&self, expected: u16) -> Result<()> {
         let actual = self.status();
         if actual != expected {
             return Err(Error::StatusMismatch { expected, actual });
         }
-        Ok(self)
+        Ok(())
     }
 
-    pub fn expect_success(self) -> Result<Self> {
+    pub fn check_success(&self) -> Result<()> {
         if !self.is_success() {
             return Err(Error::Assertion(format!(
                 "Expected success status, got {}",
                 self.status()
             )));
         }
-        Ok(self)
+        Ok(())
     }
 
-    pub fn expect_error(self) -> Result<Self> {
+    pub fn check_error(&self) -> Result<()> {
         if !self.is_error() {
             return Err(Error::Assertion(format!(
                 "Expected error status, got {}",
                 self.status()
             )));
         }
-        Ok(self)
+        Ok(())
     }
 
-    pub fn expect_json(self) -> Result<Self> {
+    pub fn check_json(&self) -> Result<()> {
         let content_type = self.header("content-type").unwrap_or("unknown");
 
         if !content_type.contains("application/json") {
@@ -111,26 +462,47 @@ impl Response {
         }
 
         self.json_value()?;
-        Ok(self)
+        Ok(())
     }
 
-    pub fn expect_text(self) -> Result<Self> {
-        self.text()?;
-        Ok(self)
+    pub fn check_xml(&self) -> Result<()> {
+        let content_type = self.header("content-type").unwrap_or("unknown");
+        if !content_type.contains("xml") {
+            return Err(Error::Xml(format!(
+                "Expected XML response, got content-type: {}",
+                content_type
+            )));
+        }
+
+        self.xml()?;
+        Ok(())
     }
 
-    pub fn expect_body_contains(self, text: &str) -> Result<Self> {
-        let body = self.text()?;
-        if !body.contains(text) {
+    pub fn check_compressed(&self, encoding: &str) -> Result<()> {
+        let actual = self.content_encoding().unwrap_or("none");
+        if !actual
+            .split(',')
+            .any(|token| token.trim().eq_ignore_ascii_case(encoding))
+        {
+            return Err(Error::Assertion(format!(
+                "Expected response compressed with '{}', got Content-Encoding '{}'",
+                encoding, actual
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn check_body_contains(&self, text: &str) -> Result<()> {
+        if !self.text()?.contains(text) {
             return Err(Error::Assertion(format!(
                 "Expected body to contain '{}', but it didn't",
                 text
             )));
         }
-        Ok(self)
+        Ok(())
     }
 
-    pub fn expect_header(self, key: &str, expected: &str) -> Result<Self> {
+    pub fn check_header(&self, key: &str, expected: &str) -> Result<()> {
         let actual = self
             .header(key)
             .ok_or_else(|| Error::Assertion(format!("Header '{}' not found", key)))?;
@@ -142,43 +514,110 @@ impl Response {
                 actual: actual.to_string(),
             });
         }
-        Ok(self)
+        Ok(())
     }
 
-    pub fn expect_content_type(self, content_type: &str) -> Result<Self> {
-        self.expect_header("content-type", content_type)
+    pub fn check_cookie(&self, name: &str, expected: &str) -> Result<()> {
+        let cookie = self.set_cookie(name)?;
+        if cookie.value != expected {
+            return Err(Error::Assertion(format!(
+                "Cookie '{}' expected value '{}', got '{}'",
+                name, expected, cookie.value
+            )));
+        }
+        Ok(())
     }
 
-    pub fn assert_field(self, path: &str, expected: impl Into<Value>) -> Result<Self> {
+    pub fn check_cookie_flags(&self, name: &str, flags: &str) -> Result<()> {
+        self.set_cookie(name)?.expect_flags(flags)
+    }
+
+    fn set_cookie(&self, name: &str) -> Result<Cookie> {
+        self.cookie(name)
+            .ok_or_else(|| Error::Assertion(format!("Cookie '{}' not set", name)))
+    }
+
+    pub fn check_field(&self, path: &str, expected: &Value) -> Result<()> {
         let json = self.json_value()?;
-        let expected_value = expected.into();
 
-        let actual_value = extract_json_path(&json, path).ok_or_else(|| Error::PathNotFound {
+        let actual = extract_json_path(&json, path).ok_or_else(|| Error::PathNotFound {
             path: path.to_string(),
         })?;
 
-        if actual_value != &expected_value {
+        if actual != expected {
             return Err(Error::FieldMismatch {
                 field: path.to_string(),
-                expected: expected_value.to_string(),
-                actual: actual_value.to_string(),
+                expected: expected.to_string(),
+                actual: actual.to_string(),
             });
         }
 
-        Ok(self)
+        Ok(())
     }
 
-    pub fn assert_field_exists(self, path: &str) -> Result<Self> {
+    pub fn check_field_exists(&self, path: &str) -> Result<()> {
         let json = self.json_value()?;
 
         extract_json_path(&json, path).ok_or_else(|| Error::PathNotFound {
             path: path.to_string(),
         })?;
 
-        Ok(self)
+        Ok(())
     }
 
-    pub fn assert_array_length(self, path: &str, expected_length: usize) -> Result<Self> {
+    pub fn check_no_graphql_errors(&self) -> Result<()> {
+        let errors = graphql::error_messages(&self.json_value()?);
+        if !errors.is_empty() {
+            return Err(Error::GraphqlErrors(errors));
+        }
+        Ok(())
+    }
+
+    pub fn check_data_field(&self, path: &str, expected: &Value) -> Result<()> {
+        let json = self.json_value()?;
+        let data_path = format!("data.{}", path);
+
+        if extract_json_path(&json, &data_path).is_none() {
+            let errors = graphql::error_messages(&json);
+            if !errors.is_empty() {
+                return Err(Error::GraphqlErrors(errors));
+            }
+        }
+
+        self.check_field(&data_path, expected)
+    }
+
+    pub fn check_schema(&self, schema: &Value) -> Result<()> {
+        let json = self.json_value()?;
+        let errors = crate::openapi::validate_schema(schema, &json);
+
+        if !errors.is_empty() {
+            return Err(Error::Assertion(format!(
+                "Response does not match schema:\n  {}",
+                errors.join("\n  ")
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub fn check_conforms_to(&self, spec: &OpenApi, operation: &str) -> Result<()> {
+        let op = spec
+            .operation(operation)
+            .ok_or_else(|| Error::OpenApi(format!("Operation '{}' not found", operation)))?;
+
+        let violations = spec.response_violations(&op, self)?;
+        if !violations.is_empty() {
+            return Err(Error::ContractViolation {
+                operation: operation.to_string(),
+                violations,
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn check_array_length(&self, path: &str, expected_length: usize) -> Result<()> {
         let json = self.json_value()?;
 
         let array = extract_json_path(&json, path)
@@ -194,11 +633,36 @@ impl Response {
             )));
         }
 
-        Ok(self)
+        Ok(())
     }
 }
 
-fn extract_json_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+fn parameter<'a>(content_type: &'a str, name: &str) -> Option<&'a str> {
+    content_type.split(';').skip(1).find_map(|parameter| {
+        let (key, value) = parameter.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case(name)
+            .then(|| value.trim().trim_matches('"'))
+    })
+}
+
+fn sniff_charset(head: &[u8]) -> Option<&'static Charset> {
+    let head = String::from_utf8_lossy(head).to_ascii_lowercase();
+    if !head.trim_start().starts_with('<') {
+        return None;
+    }
+    ["charset=", "encoding="].iter().find_map(|marker| {
+        let start = head.find(marker)? + marker.len();
+        let label: String = head[start..]
+            .trim_start_matches(['"', '\''])
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || "-_:.".contains(*c))
+            .collect();
+        Charset::for_label(label.as_bytes())
+    })
+}
+
+pub(crate) fn extract_json_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
     let parts: Vec<&str> = path.split('.').collect();
     let mut current = value;
 
@@ -244,4 +708,45 @@ mod tests {
         assert_eq!(extract_json_path(&json, "items[1].id"), Some(&json!(2)));
         assert_eq!(extract_json_path(&json, "nonexistent"), None);
     }
+
+    #[test]
+    fn test_charset_decoding() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "content-type",
+            "text/plain; charset=ISO-8859-1".parse().unwrap(),
+        );
+        let latin1 = Response::from_parts(StatusCode::OK, headers.clone(), b"caf\xe9".to_vec());
+        assert_eq!(latin1.charset(), "windows-1252");
+        assert_eq!(latin1.text().unwrap(), "café");
+
+        headers.insert(
+            "content-type",
+            "text/plain; charset=\"shift_jis\"".parse().unwrap(),
+        );
+        let sjis = Response::from_parts(StatusCode::OK, headers, b"\x93\xfa\x96\x7b".to_vec());
+        assert_eq!(sjis.text().unwrap(), "日本");
+
+        let sniffed = Response::from_parts(
+            StatusCode::OK,
+            HeaderMap::new(),
+            b"<html><meta charset=\"windows-1251\"><p>\xcf\xf0\xe8\xe2\xe5\xf2</p>".to_vec(),
+        );
+        assert!(sniffed.text().unwrap().contains("Привет"));
+
+        let bom = Response::from_parts(
+            StatusCode::OK,
+            HeaderMap::new(),
+            b"\xEF\xBB\xBF{\"id\": 1}".to_vec(),
+        );
+        assert_eq!(bom.json_value().unwrap(), json!({"id": 1}));
+
+        let binary = Response::from_parts(StatusCode::OK, HeaderMap::new(), vec![0x80, 0x81, 0xfd]);
+        assert!(binary.text().is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("body.bin");
+        binary.save_body(&path).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), vec![0x80, 0x81, 0xfd]);
+    }
 }
@@ -0,0 +1,206 @@
+use crate::config::RequestConfig;
+use std::collections::{HashMap, HashSet};
+use x_http::error::{Error, Result};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Step {
+    Run(usize),
+    Skip { index: usize, dependency: usize },
+}
+
+#[derive(Debug)]
+pub struct Plan {
+    pending: Vec<usize>,
+    dependencies: Vec<Vec<usize>>,
+    finished: HashMap<usize, bool>,
+}
+
+impl Plan {
+    pub fn new(requests: &[RequestConfig], name: Option<&str>) -> Result<Self> {
+        let mut by_name: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (index, request) in requests.iter().enumerate() {
+            by_name.entry(&request.name).or_default().push(index);
+        }
+
+        let mut dependencies = Vec::with_capacity(requests.len());
+        for request in requests {
+            let mut indices = Vec::new();
+            for dependency in &request.depends_on {
+                let targets = by_name.get(dependency.as_str()).ok_or_else(|| {
+                    Error::Config(format!(
+                        "Request '{}' depends on unknown request '{}'",
+                        request.name, dependency
+                    ))
+                })?;
+                indices.extend(targets);
+            }
+            dependencies.push(indices);
+        }
+
+        let pending = match name {
+            Some(name) => reachable(
+                &dependencies,
+                by_name.get(name).cloned().unwrap_or_default(),
+            ),
+            None => (0..requests.len()).collect(),
+        };
+
+        if pending.is_empty() {
+            return Err(Error::Config(format!(
+                "No requests found{}",
+                name.map_or(String::new(), |n| format!(" with name '{}'", n))
+            )));
+        }
+
+        let plan = Self {
+            pending,
+            dependencies,
+            finished: HashMap::new(),
+        };
+        plan.check_cycles(requests)?;
+        Ok(plan)
+    }
+
+    pub fn next(&mut self) -> Option<Step> {
+        let position = self.pending.iter().position(|index| {
+            self.dependencies[*index]
+                .iter()
+                .all(|dependency| self.finished.contains_key(dependency))
+        })?;
+        let index = self.pending.remove(position);
+
+        match self.dependencies[index]
+            .iter()
+            .find(|dependency| !self.finished[dependency])
+        {
+            Some(&dependency) => {
+                self.finished.insert(index, false);
+                Some(Step::Skip { index, dependency })
+            }
+            None => Some(Step::Run(index)),
+        }
+    }
+
+    pub fn complete(&mut self, index: usize, passed: bool) {
+        self.finished.insert(index, passed);
+    }
+
+    fn check_cycles(&self, requests: &[RequestConfig]) -> Result<()> {
+        let mut remaining = self.pending.clone();
+        let mut resolved = HashSet::new();
+
+        loop {
+            let before = remaining.len();
+            remaining.retain(|index| {
+                let ready = self.dependencies[*index]
+                    .iter()
+                    .all(|dependency| resolved.contains(dependency));
+                if ready {
+                    resolved.insert(*index);
+                }
+                !ready
+            });
+            if remaining.is_empty() {
+                return Ok(());
+            }
+            if remaining.len() == before {
+                let names: Vec<&str> = remaining
+                    .iter()
+                    .map(|index| requests[*index].name.as_str())
+                    .collect();
+                return Err(Error::Config(format!(
+                    "Dependency cycle between requests: {}",
+                    names.join(", ")
+                )));
+            }
+        }
+    }
+}
+
+fn reachable(dependencies: &[Vec<usize>], mut stack: Vec<usize>) -> Vec<usize> {
+    let mut seen = HashSet::new();
+    while let Some(index) = stack.pop() {
+        if seen.insert(index) {
+            stack.extend(&dependencies[index]);
+        }
+    }
+    let mut reachable: Vec<usize> = seen.into_iter().collect();
+    reachable.sort_unstable();
+    reachable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(name: &str, depends_on: &[&str]) -> RequestConfig {
+        RequestConfig {
+            name: name.to_string(),
+            method: "GET".to_string(),
+            url: format!("https://example.com/{}", name),
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+            ..RequestConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_plan_respects_dependencies() {
+        let requests = vec![
+            request("profile", &["login"]),
+            request("login", &[]),
+            request("health", &[]),
+            request("orders", &["login", "profile"]),
+        ];
+        let mut plan = Plan::new(&requests, None).unwrap();
+
+        assert_eq!(plan.next(), Some(Step::Run(1)));
+        assert_eq!(plan.next(), Some(Step::Run(2)));
+        assert_eq!(plan.next(), None);
+
+        plan.complete(1, true);
+        assert_eq!(plan.next(), Some(Step::Run(0)));
+        plan.complete(0, false);
+        assert_eq!(
+            plan.next(),
+            Some(Step::Skip {
+                index: 3,
+                dependency: 0
+            })
+        );
+        assert_eq!(plan.next(), None);
+    }
+
+    #[test]
+    fn test_plan_selects_dependencies_by_name() {
+        let requests = vec![
+            request("login", &[]),
+            request("health", &[]),
+            request("profile", &["login"]),
+        ];
+        let mut plan = Plan::new(&requests, Some("profile")).unwrap();
+        assert_eq!(plan.next(), Some(Step::Run(0)));
+        assert_eq!(plan.next(), None);
+        plan.complete(0, true);
+        assert_eq!(plan.next(), Some(Step::Run(2)));
+        assert_eq!(plan.next(), None);
+
+        assert!(Plan::new(&requests, Some("missing")).is_err());
+    }
+
+    #[test]
+    fn test_plan_rejects_invalid_graphs() {
+        let unknown = Plan::new(&[request("a", &["nope"])], None).unwrap_err();
+        assert!(unknown.to_string().contains("unknown request 'nope'"));
+
+        let cycle = Plan::new(
+            &[
+                request("a", &["b"]),
+                request("b", &["a"]),
+                request("c", &[]),
+            ],
+            None,
+        )
+        .unwrap_err();
+        assert!(cycle.to_string().contains("cycle between requests: a, b"));
+    }
+}
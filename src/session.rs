@@ -0,0 +1,52 @@
+use crate::cassette::Cassette;
+use crate::cookie::CookieJar;
+use crate::error::Result;
+use crate::request::Request;
+use crate::response::Response;
+
+#[derive(Debug, Default)]
+pub struct Session {
+    cassette: Option<Cassette>,
+    cookie_jar: Option<CookieJar>,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    pub fn cookie_jar(mut self, jar: CookieJar) -> Self {
+        self.cookie_jar = Some(jar);
+        self
+    }
+
+    pub fn send(&self, request: Request) -> Result<Response> {
+        let request = match &self.cookie_jar {
+            Some(jar) => request.cookie_jar(jar.clone()),
+            None => request,
+        };
+        match &self.cassette {
+            Some(cassette) => cassette.send(request),
+            None => request.send(),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        match &self.cassette {
+            Some(cassette) => cassette.save(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn finish(self) -> Result<()> {
+        match self.cassette {
+            Some(cassette) => cassette.finish(),
+            None => Ok(()),
+        }
+    }
+}
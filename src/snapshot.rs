@@ -0,0 +1,256 @@
+use crate::error::{Error, Result};
+use crate::response::Response;
+use serde_json::Value;
+use similar::TextDiff;
+use std::fs;
+use std::path::PathBuf;
+
+const REDACTED: &str = "[redacted]";
+
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    dir: Option<PathBuf>,
+    redactions: Vec<String>,
+    update: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+    Wildcard,
+}
+
+impl Snapshot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = Some(dir.into());
+        self
+    }
+
+    pub fn redact(mut self, path: impl Into<String>) -> Self {
+        self.redactions.push(path.into());
+        self
+    }
+
+    pub fn update(mut self, update: bool) -> Self {
+        self.update = Some(update);
+        self
+    }
+
+    pub fn path(&self, name: &str) -> PathBuf {
+        let dir = self.dir.clone().unwrap_or_else(|| {
+            std::env::var_os("X_HTTP_SNAPSHOT_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from("snapshots"))
+        });
+        let file: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || "-_.".contains(c) {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        dir.join(format!("{}.snap", file))
+    }
+
+    pub fn render(&self, response: &Response) -> String {
+        let mut rendered = match serde_json::from_slice::<Value>(response.body_bytes()) {
+            Ok(mut json) => {
+                for path in &self.redactions {
+                    redact(&mut json, path);
+                }
+                serde_json::to_string_pretty(&json).unwrap_or_default()
+            }
+            Err(_) => String::from_utf8_lossy(response.body_bytes()).into_owned(),
+        };
+        if !rendered.ends_with('\n') {
+            rendered.push('\n');
+        }
+        rendered
+    }
+
+    pub fn assert(&self, name: &str, response: &Response) -> Result<()> {
+        let path = self.path(name);
+        let actual = self.render(response);
+
+        let expected = match fs::read_to_string(&path) {
+            Ok(expected) => expected.replace("\r\n", "\n"),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return write(&path, &actual),
+            Err(e) => return Err(e.into()),
+        };
+
+        if expected == actual {
+            return Ok(());
+        }
+        if self.update_mode() {
+            return write(&path, &actual);
+        }
+
+        let diff = TextDiff::from_lines(&expected, &actual)
+            .unified_diff()
+            .context_radius(3)
+            .header(&path.display().to_string(), "response")
+            .to_string();
+        Err(Error::SnapshotMismatch {
+            name: name.to_string(),
+            diff,
+        })
+    }
+
+    fn update_mode(&self) -> bool {
+        self.update.unwrap_or_else(|| {
+            std::env::var("X_HTTP_UPDATE_SNAPSHOTS")
+                .is_ok_and(|value| matches!(value.as_str(), "1" | "true" | "always"))
+        })
+    }
+}
+
+fn write(path: &PathBuf, contents: &str) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+pub fn redact(value: &mut Value, path: &str) {
+    redact_segments(value, &parse_path(path));
+}
+
+fn redact_segments(value: &mut Value, segments: &[Segment]) {
+    let Some((first, rest)) = segments.split_first() else {
+        *value = Value::String(REDACTED.to_string());
+        return;
+    };
+
+    match (first, value) {
+        (Segment::Key(key), Value::Object(map)) => {
+            if let Some(child) = map.get_mut(key) {
+                redact_segments(child, rest);
+            }
+        }
+        (Segment::Index(index), Value::Array(items)) => {
+            if let Some(child) = items.get_mut(*index) {
+                redact_segments(child, rest);
+            }
+        }
+        (Segment::Wildcard, Value::Array(items)) => {
+            for child in items {
+                redact_segments(child, rest);
+            }
+        }
+        (Segment::Wildcard, Value::Object(map)) => {
+            for child in map.values_mut() {
+                redact_segments(child, rest);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn parse_path(path: &str) -> Vec<Segment> {
+    let path = path.trim();
+    let path = path.strip_prefix('$').unwrap_or(path);
+    let mut segments = Vec::new();
+
+    for part in path.split('.').filter(|p| !p.is_empty()) {
+        let (key, indices) = part.split_at(part.find('[').unwrap_or(part.len()));
+        match key {
+            "" => {}
+            "*" => segments.push(Segment::Wildcard),
+            key => segments.push(Segment::Key(key.to_string())),
+        }
+        for index in indices.split('[').filter(|i| !i.is_empty()) {
+            match index.trim_end_matches(']') {
+                "*" => segments.push(Segment::Wildcard),
+                index => {
+                    if let Ok(index) = index.parse() {
+                        segments.push(Segment::Index(index));
+                    }
+                }
+            }
+        }
+    }
+
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderMap;
+    use reqwest::StatusCode;
+    use serde_json::json;
+
+    fn response(body: &Value) -> Response {
+        Response::from_parts(StatusCode::OK, HeaderMap::new(), body.to_string())
+    }
+
+    #[test]
+    fn test_redact_paths() {
+        let mut value = json!({
+            "id": 7,
+            "createdAt": "2024-01-01",
+            "items": [{"id": 1, "name": "a"}, {"id": 2, "name": "b"}],
+            "meta": {"a": {"token": "x"}, "b": {"token": "y"}}
+        });
+
+        redact(&mut value, "$.id");
+        redact(&mut value, "$.items[*].id");
+        redact(&mut value, "$.items[1].name");
+        redact(&mut value, "meta.*.token");
+        redact(&mut value, "$.missing.path");
+
+        assert_eq!(
+            value,
+            json!({
+                "id": REDACTED,
+                "createdAt": "2024-01-01",
+                "items": [{"id": REDACTED, "name": "a"}, {"id": REDACTED, "name": REDACTED}],
+                "meta": {"a": {"token": REDACTED}, "b": {"token": REDACTED}}
+            })
+        );
+    }
+
+    #[test]
+    fn test_snapshot_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = Snapshot::new()
+            .dir(dir.path())
+            .redact("$.createdAt")
+            .update(false);
+
+        let first = response(&json!({"name": "John", "createdAt": "2024-01-01"}));
+        settings.assert("user profile", &first).unwrap();
+
+        let path = dir.path().join("user_profile.snap");
+        let stored = fs::read_to_string(&path).unwrap();
+        assert!(stored.contains("\"createdAt\": \"[redacted]\""));
+
+        let volatile = response(&json!({"name": "John", "createdAt": "2025-06-30"}));
+        first
+            .expect_snapshot_with("user profile", &settings)
+            .unwrap();
+        settings.assert("user profile", &volatile).unwrap();
+
+        let changed = response(&json!({"name": "Jane", "createdAt": "2024-01-01"}));
+        let error = settings.assert("user profile", &changed).unwrap_err();
+        let message = error.to_string();
+        assert!(message.contains("-  \"name\": \"John\""));
+        assert!(message.contains("+  \"name\": \"Jane\""));
+
+        settings
+            .clone()
+            .update(true)
+            .assert("user profile", &changed)
+            .unwrap();
+        assert!(fs::read_to_string(&path).unwrap().contains("Jane"));
+    }
+}
@@ -0,0 +1,386 @@
+use crate::error::{Error, Result};
+use crate::request::Request;
+use crate::response::extract_json_path;
+use reqwest::header::HeaderMap;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const DEFAULT_RETRY: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Event {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub event: String,
+    pub data: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry: Option<u64>,
+}
+
+#[derive(Debug)]
+pub struct EventStreamBuilder {
+    request: Request,
+    reconnect: bool,
+    last_event_id: Option<String>,
+}
+
+pub struct EventStream {
+    status: u16,
+    headers: HeaderMap,
+    events: Receiver<Result<Event>>,
+    last_event_id: Option<String>,
+    closed: Arc<AtomicBool>,
+}
+
+#[derive(Debug, Default)]
+struct Parser {
+    id: Option<String>,
+    event: Option<String>,
+    data: Vec<String>,
+    retry: Option<u64>,
+}
+
+impl EventStreamBuilder {
+    pub fn reconnect(mut self, reconnect: bool) -> Self {
+        self.reconnect = reconnect;
+        self
+    }
+
+    pub fn last_event_id(mut self, id: impl Into<String>) -> Self {
+        self.last_event_id = Some(id.into());
+        self
+    }
+
+    pub fn connect(self) -> Result<EventStream> {
+        let request = self.request.no_timeout();
+        let response = open(&request, self.last_event_id.as_deref())?;
+        let status = response.status().as_u16();
+        let headers = response.headers().clone();
+
+        let (sender, events) = mpsc::channel();
+        let closed = Arc::new(AtomicBool::new(false));
+        let reconnect = self.reconnect.then(|| closed.clone());
+        let last_event_id = self.last_event_id.clone();
+        thread::spawn(move || {
+            pump(response, request, reconnect, last_event_id, sender);
+        });
+
+        Ok(EventStream {
+            status,
+            headers,
+            events,
+            last_event_id: self.last_event_id,
+            closed,
+        })
+    }
+}
+
+impl EventStream {
+    pub fn builder(request: Request) -> EventStreamBuilder {
+        EventStreamBuilder {
+            request,
+            reconnect: false,
+            last_event_id: None,
+        }
+    }
+
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.last_event_id.as_deref()
+    }
+
+    pub fn next_event(&mut self) -> Result<Option<Event>> {
+        match self.events.recv() {
+            Ok(event) => self.track(event).map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+
+    pub fn next_event_within(&mut self, timeout: Duration) -> Result<Option<Event>> {
+        match self.events.recv_timeout(timeout) {
+            Ok(event) => self.track(event).map(Some),
+            Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => Ok(None),
+        }
+    }
+
+    pub fn expect_event_within(&mut self, name: &str, timeout: Duration) -> Result<Event> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.next_event_within(remaining)? {
+                Some(event) if event.event == name => return Ok(event),
+                Some(_) if !remaining.is_zero() => continue,
+                _ => {
+                    return Err(Error::Assertion(format!(
+                        "No '{}' event received within {:?}",
+                        name, timeout
+                    )))
+                }
+            }
+        }
+    }
+
+    fn track(&mut self, event: Result<Event>) -> Result<Event> {
+        let event = event?;
+        if let Some(id) = &event.id {
+            self.last_event_id = Some(id.clone());
+        }
+        Ok(event)
+    }
+}
+
+impl Drop for EventStream {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Iterator for EventStream {
+    type Item = Result<Event>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_event().transpose()
+    }
+}
+
+impl Request {
+    pub fn sse(self) -> Result<EventStream> {
+        EventStream::builder(self).connect()
+    }
+}
+
+impl Event {
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(serde_json::from_str(&self.data)?)
+    }
+
+    pub fn json_value(&self) -> Result<Value> {
+        self.json()
+    }
+
+    pub fn expect_data(self, expected: &str) -> Result<Self> {
+        if self.data != expected {
+            return Err(Error::Assertion(format!(
+                "Expected event data '{}', got '{}'",
+                expected, self.data
+            )));
+        }
+        Ok(self)
+    }
+
+    pub fn assert_field(self, path: &str, expected: impl Into<Value>) -> Result<Self> {
+        let json = self.json_value()?;
+        let expected = expected.into();
+        let actual = extract_json_path(&json, path).ok_or_else(|| Error::PathNotFound {
+            path: path.to_string(),
+        })?;
+
+        if actual != &expected {
+            return Err(Error::FieldMismatch {
+                field: path.to_string(),
+                expected: expected.to_string(),
+                actual: actual.to_string(),
+            });
+        }
+        Ok(self)
+    }
+}
+
+impl Parser {
+    fn feed(&mut self, line: &str) -> Option<Event> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            "id" if !value.contains('\0') => self.id = Some(value.to_string()),
+            "retry" => {
+                if let Ok(retry) = value.parse() {
+                    self.retry = Some(retry);
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<Event> {
+        let event = self.event.take();
+        if self.data.is_empty() {
+            return None;
+        }
+
+        Some(Event {
+            id: self.id.clone(),
+            event: event.unwrap_or_else(|| "message".to_string()),
+            data: std::mem::take(&mut self.data).join("\n"),
+            retry: self.retry.take(),
+        })
+    }
+}
+
+fn open(request: &Request, last_event_id: Option<&str>) -> Result<reqwest::blocking::Response> {
+    let mut request = request.clone().header("Accept", "text/event-stream");
+    if let Some(id) = last_event_id {
+        request = request.header("Last-Event-ID", id);
+    }
+
+    let response = request.dispatch()?.response;
+    if !response.status().is_success() {
+        return Err(Error::HttpStatus {
+            status: response.status().as_u16(),
+            message: "expected an event stream".to_string(),
+        });
+    }
+    Ok(response)
+}
+
+fn pump(
+    response: impl Read,
+    request: Request,
+    reconnect: Option<Arc<AtomicBool>>,
+    mut last_event_id: Option<String>,
+    sender: Sender<Result<Event>>,
+) {
+    let mut retry = DEFAULT_RETRY;
+    let mut reader: Box<dyn Read> = Box::new(response);
+
+    loop {
+        let mut parser = Parser {
+            id: last_event_id.clone(),
+            ..Parser::default()
+        };
+        for line in BufReader::new(reader).lines() {
+            let Ok(line) = line else { break };
+            let event = parser.feed(line.trim_end_matches('\r'));
+            if let Some(ms) = parser.retry.or(event.as_ref().and_then(|e| e.retry)) {
+                retry = Duration::from_millis(ms);
+            }
+            let Some(event) = event else { continue };
+            last_event_id = event.id.clone();
+            if sender.send(Ok(event)).is_err() {
+                return;
+            }
+        }
+
+        let closed = || {
+            reconnect
+                .as_ref()
+                .is_none_or(|closed| closed.load(Ordering::Relaxed))
+        };
+        if closed() {
+            return;
+        }
+        thread::sleep(retry);
+        if closed() {
+            return;
+        }
+        reader = match open(&request, last_event_id.as_deref()) {
+            Ok(response) => Box::new(response),
+            Err(e) => {
+                let _ = sender.send(Err(e));
+                return;
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockServer, Stub};
+
+    #[test]
+    fn test_parse_events() {
+        let mut parser = Parser::default();
+        let lines = [
+            ": keep-alive",
+            "retry: 500",
+            "",
+            "id: 1",
+            "event: order",
+            "data: {\"id\": 7,",
+            "data:  \"status\": \"paid\"}",
+            "",
+            "data",
+            "",
+        ];
+        let events: Vec<Event> = lines.iter().filter_map(|line| parser.feed(line)).collect();
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].id.as_deref(), Some("1"));
+        assert_eq!(events[0].event, "order");
+        assert_eq!(events[0].data, "{\"id\": 7,\n \"status\": \"paid\"}");
+        assert_eq!(events[0].retry, Some(500));
+        assert_eq!(events[1].event, "message");
+        assert_eq!(events[1].id.as_deref(), Some("1"));
+        assert_eq!(events[1].data, "");
+    }
+
+    #[test]
+    fn test_event_stream_with_reconnect() {
+        let server = MockServer::start().unwrap();
+        server.mock(
+            Stub::get("/events")
+                .header("Last-Event-ID", "2")
+                .response_header("Content-Type", "text/event-stream")
+                .response_body("id: 3\nevent: done\ndata: bye\n\n"),
+        );
+        server.mock(
+            Stub::get("/events")
+                .response_header("Content-Type", "text/event-stream")
+                .response_body(
+                    "retry: 10\n\nid: 1\ndata: hello\n\nid: 2\nevent: order\ndata: {\"id\": 7}\n\n",
+                ),
+        );
+
+        let mut stream = EventStream::builder(Request::get(server.url("/events")))
+            .reconnect(true)
+            .connect()
+            .unwrap();
+        assert_eq!(stream.status(), 200);
+
+        stream
+            .expect_event_within("order", Duration::from_secs(2))
+            .unwrap()
+            .assert_field("id", 7)
+            .unwrap();
+        assert_eq!(stream.last_event_id(), Some("2"));
+
+        stream
+            .expect_event_within("done", Duration::from_secs(2))
+            .unwrap()
+            .expect_data("bye")
+            .unwrap();
+
+        drop(stream);
+        thread::sleep(Duration::from_millis(100));
+        let connections = server.received_requests().len();
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(server.received_requests().len(), connections);
+
+        let error = Request::get(server.url("/missing")).sse().err().unwrap();
+        assert!(matches!(error, Error::HttpStatus { status: 404, .. }));
+    }
+}
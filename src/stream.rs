@@ -0,0 +1,178 @@
+use crate::error::{Error, Result};
+use crate::request::{Exchange, HttpClient, Request};
+use crate::response::Response;
+use reqwest::header::HeaderMap;
+use std::io::{self, Read, Write};
+
+pub struct StreamingResponse {
+    exchange: Exchange,
+    max_body_size: Option<u64>,
+    decompress: bool,
+}
+
+pub struct Chunks {
+    response: StreamingResponse,
+    size: usize,
+    done: bool,
+}
+
+impl Request {
+    pub fn send_streaming(self) -> Result<StreamingResponse> {
+        let client = HttpClient::new(&self)?;
+        self.send_streaming_with(&client)
+    }
+
+    pub fn send_streaming_with(self, client: &HttpClient) -> Result<StreamingResponse> {
+        let max_body_size = self.body_limit();
+        let decompress = self.decompresses();
+        let exchange = self.dispatch_with(client)?;
+        Ok(StreamingResponse {
+            exchange,
+            max_body_size,
+            decompress,
+        })
+    }
+}
+
+impl StreamingResponse {
+    pub fn status(&self) -> u16 {
+        self.exchange.response.status().as_u16()
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.exchange.response.status().is_success()
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        self.exchange.response.headers()
+    }
+
+    pub fn header(&self, key: &str) -> Option<&str> {
+        self.headers().get(key)?.to_str().ok()
+    }
+
+    pub fn url(&self) -> &str {
+        self.exchange.response.url().as_str()
+    }
+
+    pub fn content_length(&self) -> Option<u64> {
+        self.exchange.response.content_length()
+    }
+
+    pub fn chunks(self, size: usize) -> Chunks {
+        Chunks {
+            response: self,
+            size: size.max(1),
+            done: false,
+        }
+    }
+
+    pub fn copy_to<W: Write + ?Sized>(&mut self, writer: &mut W) -> Result<u64> {
+        Ok(io::copy(self, writer)?)
+    }
+
+    pub fn into_response(self) -> Result<Response> {
+        let exchange = self.exchange;
+        Response::from_reqwest(
+            exchange.response,
+            exchange.duration,
+            self.max_body_size,
+            self.decompress,
+        )
+        .map(|response| {
+            response.with_exchange(exchange.info, exchange.redirects, exchange.started_at)
+        })
+    }
+}
+
+impl Read for StreamingResponse {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.exchange.response.read(buf)
+    }
+}
+
+impl Iterator for Chunks {
+    type Item = Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let mut chunk = vec![0; self.size];
+        match self.response.read(&mut chunk) {
+            Ok(0) => {
+                self.done = true;
+                None
+            }
+            Ok(read) => {
+                chunk.truncate(read);
+                Some(Ok(chunk))
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e.into()))
+            }
+        }
+    }
+}
+
+pub(crate) fn read_limited(
+    mut reader: impl Read,
+    content_length: Option<u64>,
+    limit: u64,
+) -> Result<Vec<u8>> {
+    if content_length.is_some_and(|length| length > limit) {
+        return Err(Error::BodyTooLarge { limit });
+    }
+
+    let mut body = Vec::new();
+    reader.by_ref().take(limit + 1).read_to_end(&mut body)?;
+    if body.len() as u64 > limit {
+        return Err(Error::BodyTooLarge { limit });
+    }
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockServer, Stub};
+
+    #[test]
+    fn test_streaming_and_body_limits() {
+        let server = MockServer::start().unwrap();
+        let body = "x".repeat(10_000);
+        server.mock(Stub::get("/large").response_body(body.clone()));
+
+        let response = Request::get(server.url("/large")).send_streaming().unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.content_length(), Some(10_000));
+        let chunks: Vec<Vec<u8>> = response.chunks(4096).map(|c| c.unwrap()).collect();
+        assert!(chunks.iter().all(|chunk| chunk.len() <= 4096));
+        assert_eq!(chunks.concat(), body.as_bytes());
+
+        let mut copied = Vec::new();
+        let mut response = Request::get(server.url("/large")).send_streaming().unwrap();
+        assert_eq!(response.copy_to(&mut copied).unwrap(), 10_000);
+
+        let error = Request::get(server.url("/large"))
+            .max_body_size(1024)
+            .send()
+            .unwrap_err();
+        assert!(matches!(error, Error::BodyTooLarge { limit: 1024 }));
+
+        let mut reader = body.as_bytes();
+        let error = read_limited(&mut reader, None, 9_999).unwrap_err();
+        assert!(matches!(error, Error::BodyTooLarge { .. }));
+
+        Request::get(server.url("/large"))
+            .max_body_size(10_000)
+            .send_streaming()
+            .unwrap()
+            .into_response()
+            .unwrap()
+            .expect_body_contains("xxx")
+            .unwrap();
+    }
+}
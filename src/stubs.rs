@@ -0,0 +1,173 @@
+use crate::config::parse_method;
+use colored::Colorize;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::thread;
+use std::time::Duration;
+use x_http::error::{Error, Result};
+use x_http::{MockServer, Stub};
+
+#[derive(Debug, Deserialize)]
+struct StubFile {
+    #[serde(default)]
+    stubs: Vec<StubConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StubConfig {
+    method: Option<String>,
+    path: String,
+    #[serde(default)]
+    query: HashMap<String, String>,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    body: Option<String>,
+    body_contains: Option<String>,
+    json: Option<serde_json::Value>,
+    #[serde(default)]
+    response: ResponseConfig,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ResponseConfig {
+    status: Option<u16>,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    body: Option<String>,
+    json: Option<serde_json::Value>,
+    delay_ms: Option<u64>,
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Stub>> {
+    let file: StubFile = toml::from_str(contents)
+        .map_err(|e| Error::Config(format!("Failed to parse stubs file: {}", e)))?;
+    file.stubs.into_iter().map(build_stub).collect()
+}
+
+fn build_stub(config: StubConfig) -> Result<Stub> {
+    let mut stub = match config.method.as_deref() {
+        None | Some("ANY") | Some("any") => Stub::any(config.path),
+        Some(method) => Stub::new(parse_method(method)?, config.path),
+    };
+
+    for (key, value) in config.query {
+        stub = stub.query(key, value);
+    }
+    for (key, value) in config.headers {
+        stub = stub.header(key, value);
+    }
+    if let Some(body) = config.body {
+        stub = stub.body(body);
+    }
+    if let Some(text) = config.body_contains {
+        stub = stub.body_contains(text);
+    }
+    if let Some(json) = &config.json {
+        stub = stub.json_body(json)?;
+    }
+
+    let response = config.response;
+    stub = stub.status(response.status.unwrap_or(200));
+    if let Some(json) = &response.json {
+        stub = stub.response_json(json)?;
+    }
+    if let Some(body) = response.body {
+        stub = stub.response_body(body);
+    }
+    for (key, value) in response.headers {
+        stub = stub.response_header(key, value);
+    }
+    if let Some(delay) = response.delay_ms {
+        stub = stub.delay(Duration::from_millis(delay));
+    }
+
+    Ok(stub)
+}
+
+pub fn serve(path: &str, host: &str, port: u16) -> Result<()> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| Error::Config(format!("Failed to read stubs file: {}", e)))?;
+    let stubs = parse(&contents)?;
+    let count = stubs.len();
+
+    let server = MockServer::start_on(&format!("{}:{}", host, port))?;
+    for stub in stubs {
+        server.mock(stub);
+    }
+
+    println!(
+        "🎭 Mock server listening on {} with {} stub(s)",
+        server.uri().bold(),
+        count
+    );
+    println!("{}", "Press Ctrl+C to stop".dimmed());
+
+    loop {
+        thread::sleep(Duration::from_millis(200));
+        for request in server.take_received_requests() {
+            let status = if request.status < 400 {
+                request.status.to_string().green()
+            } else {
+                request.status.to_string().red()
+            };
+            println!("  {} {} → {}", request.method.bold(), request.path, status);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use x_http::Request;
+
+    #[test]
+    fn test_serve_stubs_from_toml() {
+        let stubs = parse(
+            r#"
+[[stubs]]
+method = "GET"
+path = "/users"
+query = { page = "1" }
+
+[stubs.response]
+json = { users = [{ id = 1, name = "John" }] }
+
+[[stubs]]
+method = "POST"
+path = "/users"
+body_contains = "John"
+
+[stubs.response]
+status = 201
+headers = { Location = "/users/2" }
+"#,
+        )
+        .unwrap();
+        assert_eq!(stubs.len(), 2);
+
+        let server = MockServer::start().unwrap();
+        for stub in stubs {
+            server.mock(stub);
+        }
+
+        Request::get(server.url("/users?page=1"))
+            .send()
+            .unwrap()
+            .expect_json()
+            .unwrap()
+            .assert_field("users[0].name", "John")
+            .unwrap();
+
+        Request::post(server.url("/users"))
+            .text(r#"{"name": "John"}"#)
+            .send()
+            .unwrap()
+            .expect_status(201)
+            .unwrap()
+            .expect_header("location", "/users/2")
+            .unwrap();
+
+        assert!(parse("[[stubs]]\nmethod = \"FETCH\"\npath = \"/\"").is_err());
+    }
+}
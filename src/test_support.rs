@@ -0,0 +1,36 @@
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::Message;
+
+pub async fn ws_echo_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+                while let Some(Ok(message)) = socket.next().await {
+                    match message {
+                        Message::Text(text) if text == "bye" => {
+                            let frame = CloseFrame {
+                                code: CloseCode::from(4000),
+                                reason: "bye".into(),
+                            };
+                            let _ = socket.close(Some(frame)).await;
+                            break;
+                        }
+                        Message::Text(_) | Message::Binary(_) => {
+                            socket.send(message).await.unwrap();
+                        }
+                        _ => {}
+                    }
+                }
+            });
+        }
+    });
+
+    format!("ws://{}", address)
+}
@@ -0,0 +1,308 @@
+use crate::error::{Error, Result};
+use crate::response::extract_json_path;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::borrow::Cow;
+use std::io::ErrorKind;
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+use tungstenite::client::IntoClientRequest;
+use tungstenite::http::{HeaderName, HeaderValue};
+use tungstenite::protocol::frame::coding::CloseCode;
+use tungstenite::protocol::CloseFrame;
+use tungstenite::stream::MaybeTlsStream;
+
+type Socket = tungstenite::WebSocket<MaybeTlsStream<TcpStream>>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    Text(String),
+    Binary(Vec<u8>),
+    Close(Option<u16>),
+}
+
+#[derive(Debug)]
+pub struct WebSocketBuilder {
+    url: String,
+    headers: Vec<(String, String)>,
+    timeout: Duration,
+}
+
+pub struct WebSocket {
+    socket: Socket,
+    timeout: Duration,
+}
+
+impl WebSocketBuilder {
+    pub fn header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((key.into(), value.into()));
+        self
+    }
+
+    pub fn bearer_auth(self, token: impl AsRef<str>) -> Self {
+        self.header("Authorization", format!("Bearer {}", token.as_ref()))
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn connect(self) -> Result<WebSocket> {
+        let mut request = self.url.as_str().into_client_request().map_err(ws_error)?;
+        for (key, value) in &self.headers {
+            let name = HeaderName::try_from(key.as_str())
+                .map_err(|e| Error::WebSocket(format!("Invalid header '{}': {}", key, e)))?;
+            let value = HeaderValue::try_from(value.as_str())
+                .map_err(|e| Error::WebSocket(format!("Invalid header '{}': {}", key, e)))?;
+            request.headers_mut().insert(name, value);
+        }
+
+        let (socket, _) = tungstenite::connect(request).map_err(ws_error)?;
+        Ok(WebSocket {
+            socket,
+            timeout: self.timeout,
+        })
+    }
+}
+
+impl WebSocket {
+    pub fn builder(url: impl Into<String>) -> WebSocketBuilder {
+        WebSocketBuilder {
+            url: url.into(),
+            headers: Vec::new(),
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    pub fn connect(url: impl Into<String>) -> Result<Self> {
+        Self::builder(url).connect()
+    }
+
+    pub fn send_text(&mut self, text: impl Into<String>) -> Result<()> {
+        self.send(tungstenite::Message::Text(text.into()))
+    }
+
+    pub fn send_binary(&mut self, bytes: impl Into<Vec<u8>>) -> Result<()> {
+        self.send(tungstenite::Message::Binary(bytes.into()))
+    }
+
+    pub fn send_json<T: Serialize>(&mut self, value: &T) -> Result<()> {
+        self.send_text(serde_json::to_string(value)?)
+    }
+
+    pub fn next_message(&mut self, timeout: Duration) -> Result<Option<Message>> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(None);
+            }
+            self.set_read_timeout(remaining)?;
+
+            match self.socket.read() {
+                Ok(tungstenite::Message::Text(text)) => return Ok(Some(Message::Text(text))),
+                Ok(tungstenite::Message::Binary(bytes)) => return Ok(Some(Message::Binary(bytes))),
+                Ok(tungstenite::Message::Close(frame)) => {
+                    return Ok(Some(Message::Close(frame.map(|f| f.code.into()))))
+                }
+                Ok(_) => continue,
+                Err(tungstenite::Error::Io(e))
+                    if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+                {
+                    return Ok(None)
+                }
+                Err(tungstenite::Error::ConnectionClosed) => return Ok(Some(Message::Close(None))),
+                Err(e) => return Err(ws_error(e)),
+            }
+        }
+    }
+
+    pub fn expect_message(&mut self) -> Result<Message> {
+        self.expect_message_within(self.timeout)
+    }
+
+    pub fn expect_message_within(&mut self, timeout: Duration) -> Result<Message> {
+        match self.next_message(timeout)? {
+            Some(Message::Close(code)) => Err(Error::Assertion(format!(
+                "Expected a message, but the connection was closed{}",
+                format_code(code)
+            ))),
+            Some(message) => Ok(message),
+            None => Err(Error::Assertion(format!(
+                "No message received within {:?}",
+                timeout
+            ))),
+        }
+    }
+
+    pub fn expect_close(&mut self, code: u16) -> Result<()> {
+        let timeout = self.timeout;
+        loop {
+            match self.next_message(timeout)? {
+                Some(Message::Close(actual)) if actual == Some(code) => return Ok(()),
+                Some(Message::Close(actual)) => {
+                    return Err(Error::Assertion(format!(
+                        "Expected close code {}, got{}",
+                        code,
+                        format_code(actual)
+                    )))
+                }
+                Some(_) => continue,
+                None => {
+                    return Err(Error::Assertion(format!(
+                        "Connection was not closed within {:?}",
+                        timeout
+                    )))
+                }
+            }
+        }
+    }
+
+    pub fn close(&mut self, code: u16, reason: &str) -> Result<()> {
+        let frame = CloseFrame {
+            code: CloseCode::from(code),
+            reason: Cow::Owned(reason.to_string()),
+        };
+        match self.socket.close(Some(frame)) {
+            Ok(()) | Err(tungstenite::Error::ConnectionClosed) => {}
+            Err(e) => return Err(ws_error(e)),
+        }
+
+        let deadline = Instant::now() + self.timeout;
+        while Instant::now() < deadline {
+            match self.next_message(deadline.saturating_duration_since(Instant::now())) {
+                Ok(Some(Message::Close(_))) | Ok(None) | Err(_) => break,
+                Ok(Some(_)) => continue,
+            }
+        }
+        Ok(())
+    }
+
+    fn send(&mut self, message: tungstenite::Message) -> Result<()> {
+        self.socket.send(message).map_err(ws_error)
+    }
+
+    fn set_read_timeout(&mut self, timeout: Duration) -> Result<()> {
+        let stream = match self.socket.get_mut() {
+            MaybeTlsStream::Plain(stream) => stream,
+            MaybeTlsStream::NativeTls(stream) => stream.get_mut(),
+            _ => return Ok(()),
+        };
+        stream.set_read_timeout(Some(timeout))?;
+        Ok(())
+    }
+}
+
+impl Message {
+    pub fn text(&self) -> Result<&str> {
+        match self {
+            Message::Text(text) => Ok(text),
+            Message::Binary(bytes) => std::str::from_utf8(bytes)
+                .map_err(|_| Error::Assertion("Binary message is not valid UTF-8".to_string())),
+            Message::Close(_) => Err(Error::Assertion("Close frame has no body".to_string())),
+        }
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(serde_json::from_str(self.text()?)?)
+    }
+
+    pub fn json_value(&self) -> Result<Value> {
+        self.json()
+    }
+
+    pub fn expect_text(self, expected: &str) -> Result<Self> {
+        let actual = self.text()?;
+        if actual != expected {
+            return Err(Error::Assertion(format!(
+                "Expected message '{}', got '{}'",
+                expected, actual
+            )));
+        }
+        Ok(self)
+    }
+
+    pub fn expect_contains(self, text: &str) -> Result<Self> {
+        if !self.text()?.contains(text) {
+            return Err(Error::Assertion(format!(
+                "Expected message to contain '{}', but it didn't",
+                text
+            )));
+        }
+        Ok(self)
+    }
+
+    pub fn assert_field(self, path: &str, expected: impl Into<Value>) -> Result<Self> {
+        let json = self.json_value()?;
+        let expected = expected.into();
+        let actual = extract_json_path(&json, path).ok_or_else(|| Error::PathNotFound {
+            path: path.to_string(),
+        })?;
+
+        if actual != &expected {
+            return Err(Error::FieldMismatch {
+                field: path.to_string(),
+                expected: expected.to_string(),
+                actual: actual.to_string(),
+            });
+        }
+        Ok(self)
+    }
+}
+
+fn format_code(code: Option<u16>) -> String {
+    code.map(|code| format!(" with code {}", code))
+        .unwrap_or_default()
+}
+
+fn ws_error(error: tungstenite::Error) -> Error {
+    Error::WebSocket(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::ws_echo_server;
+    use serde_json::json;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_websocket_exchange() {
+        let url = ws_echo_server().await;
+
+        tokio::task::spawn_blocking(move || {
+            let mut ws = WebSocket::builder(&url)
+                .bearer_auth("token")
+                .timeout(Duration::from_secs(2))
+                .connect()
+                .unwrap();
+
+            ws.send_text("hello").unwrap();
+            ws.expect_message().unwrap().expect_text("hello").unwrap();
+
+            ws.send_json(&json!({"type": "ping", "items": [1, 2]}))
+                .unwrap();
+            ws.expect_message()
+                .unwrap()
+                .assert_field("type", "ping")
+                .unwrap()
+                .assert_field("items[1]", 2)
+                .unwrap();
+
+            ws.send_binary(vec![1, 2, 3]).unwrap();
+            assert_eq!(ws.expect_message().unwrap(), Message::Binary(vec![1, 2, 3]));
+
+            let error = ws
+                .expect_message_within(Duration::from_millis(100))
+                .unwrap_err();
+            assert!(error.to_string().contains("No message received"));
+
+            ws.send_text("bye").unwrap();
+            ws.expect_close(4000).unwrap();
+        })
+        .await
+        .unwrap();
+    }
+}
@@ -0,0 +1,262 @@
+use crate::config::{Config, RequestConfig, WsStep};
+use crate::output::AssertionOutcome;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use colored::Colorize;
+use std::io::{self, BufRead};
+use std::sync::mpsc::{self, TryRecvError};
+use std::thread;
+use std::time::Duration;
+use x_http::error::{Error, Result};
+use x_http::websocket::Message;
+use x_http::WebSocket;
+
+pub fn run_script(
+    config: &Config,
+    request_config: &RequestConfig,
+) -> Result<Vec<AssertionOutcome>> {
+    let mut builder = WebSocket::builder(config.substitute_variables(&request_config.url));
+    for (key, value) in &request_config.headers {
+        builder = builder.header(key, config.substitute_variables(value));
+    }
+    if let Some(timeout) = request_config.timeout {
+        builder = builder.timeout(Duration::from_secs(timeout));
+    }
+    let mut ws = builder.connect()?;
+
+    let mut outcomes = Vec::new();
+    for step in &request_config.ws {
+        send_step(&mut ws, config, step)?;
+
+        let outcome = expect_step(&mut ws, step);
+        let failed = outcome.as_ref().is_some_and(|o| !o.passed);
+        outcomes.extend(outcome);
+        if failed {
+            break;
+        }
+
+        if let Some(code) = step.close {
+            ws.close(code, "")?;
+        }
+    }
+
+    Ok(outcomes)
+}
+
+fn send_step(ws: &mut WebSocket, config: &Config, step: &WsStep) -> Result<()> {
+    if let Some(text) = &step.send {
+        ws.send_text(config.substitute_variables(text))?;
+    }
+    if let Some(json) = &step.send_json {
+        ws.send_json(&config.substitute_json(json))?;
+    }
+    if let Some(encoded) = &step.send_binary {
+        let bytes = STANDARD
+            .decode(encoded)
+            .map_err(|e| Error::Config(format!("Invalid base64 in send_binary: {}", e)))?;
+        ws.send_binary(bytes)?;
+    }
+    Ok(())
+}
+
+fn expect_step(ws: &mut WebSocket, step: &WsStep) -> Option<AssertionOutcome> {
+    if let Some(code) = step.expect_close {
+        return Some(AssertionOutcome::from_result(
+            format!("connection closed with code {}", code),
+            ws.expect_close(code),
+        ));
+    }
+    if step.expect.is_none() && step.expect_contains.is_none() && step.expect_json.is_empty() {
+        return None;
+    }
+
+    let mut description = Vec::new();
+    if let Some(text) = &step.expect {
+        description.push(format!("message is '{}'", text));
+    }
+    if let Some(text) = &step.expect_contains {
+        description.push(format!("message contains '{}'", text));
+    }
+    for (path, value) in &step.expect_json {
+        description.push(format!("field '{}' is {}", path, value));
+    }
+
+    let result = receive(ws, step).and_then(|message| check_message(message, step));
+    Some(AssertionOutcome::from_result(
+        description.join(", "),
+        result,
+    ))
+}
+
+fn receive(ws: &mut WebSocket, step: &WsStep) -> Result<Message> {
+    match step.timeout_ms {
+        Some(timeout) => ws.expect_message_within(Duration::from_millis(timeout)),
+        None => ws.expect_message(),
+    }
+}
+
+fn check_message(mut message: Message, step: &WsStep) -> Result<()> {
+    if let Some(text) = &step.expect {
+        message = message.expect_text(text)?;
+    }
+    if let Some(text) = &step.expect_contains {
+        message = message.expect_contains(text)?;
+    }
+    for (path, value) in &step.expect_json {
+        message = message.assert_field(path, value.clone())?;
+    }
+    Ok(())
+}
+
+pub fn repl(url: &str, headers: &[String]) -> Result<()> {
+    let mut builder = WebSocket::builder(url);
+    for header in headers {
+        if let Some((key, value)) = header.split_once(':') {
+            builder = builder.header(key.trim(), value.trim());
+        }
+    }
+    let mut ws = builder.connect()?;
+
+    println!("🔌 Connected to {}", url.bold());
+    println!(
+        "{}",
+        "Type a message to send it. /json <value>, /binary <base64>, /close [code], /quit".dimmed()
+    );
+
+    let (sender, lines) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines().map_while(|line| line.ok()) {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    loop {
+        match ws.next_message(Duration::from_millis(100))? {
+            Some(Message::Close(code)) => {
+                let code = code.map_or_else(|| "no code".to_string(), |c| c.to_string());
+                println!("🔌 Connection closed by server ({})", code);
+                return Ok(());
+            }
+            Some(message) => print_incoming(&message),
+            None => {}
+        }
+
+        let line = match lines.try_recv() {
+            Ok(line) => line,
+            Err(TryRecvError::Empty) => continue,
+            Err(TryRecvError::Disconnected) => "/quit".to_string(),
+        };
+        if let Err(e) = handle_line(&mut ws, line.trim()) {
+            eprintln!("{} {}", "Error:".red().bold(), e);
+        }
+        if line.trim() == "/quit" || line.trim().starts_with("/close") {
+            return Ok(());
+        }
+    }
+}
+
+fn handle_line(ws: &mut WebSocket, line: &str) -> Result<()> {
+    let (command, argument) = line.split_once(' ').unwrap_or((line, ""));
+    match command {
+        "" => Ok(()),
+        "/quit" => ws.close(1000, ""),
+        "/close" => {
+            let code = match argument.trim() {
+                "" => 1000,
+                code => code
+                    .parse()
+                    .map_err(|_| Error::Config(format!("Invalid close code: {}", code)))?,
+            };
+            ws.close(code, "")
+        }
+        "/json" => {
+            let json: serde_json::Value = serde_json::from_str(argument)?;
+            ws.send_json(&json)
+        }
+        "/binary" => {
+            let bytes = STANDARD
+                .decode(argument.trim())
+                .map_err(|e| Error::Config(format!("Invalid base64: {}", e)))?;
+            ws.send_binary(bytes)
+        }
+        _ => ws.send_text(line),
+    }
+}
+
+fn print_incoming(message: &Message) {
+    match message {
+        Message::Text(text) => match serde_json::from_str::<serde_json::Value>(text) {
+            Ok(json) => println!(
+                "{} {}",
+                "←".green(),
+                serde_json::to_string_pretty(&json).unwrap_or_else(|_| text.clone())
+            ),
+            Err(_) => println!("{} {}", "←".green(), text),
+        },
+        Message::Binary(bytes) => println!(
+            "{} {} {}",
+            "←".green(),
+            format!("[{} bytes]", bytes.len()).dimmed(),
+            STANDARD.encode(bytes)
+        ),
+        Message::Close(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::ws_echo_server;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_run_ws_script_from_config() {
+        let url = ws_echo_server().await;
+        let config: Config = toml::from_str(&format!(
+            r#"
+[variables]
+user = "john"
+note = 'say "hi"'
+
+[[requests]]
+name = "chat"
+method = "GET"
+url = "{url}"
+
+[[requests.ws]]
+send = "hello {{{{user}}}}"
+expect = "hello john"
+
+[[requests.ws]]
+send_json = {{ type = "subscribe", channel = "{{{{note}}}}" }}
+expect_json = {{ type = "subscribe", channel = 'say "hi"' }}
+
+[[requests.ws]]
+send = "ping"
+expect_contains = "pong"
+timeout_ms = 500
+
+[[requests.ws]]
+send = "never reached"
+expect = "never reached"
+"#
+        ))
+        .unwrap();
+
+        let outcomes =
+            tokio::task::spawn_blocking(move || run_script(&config, &config.requests[0]).unwrap())
+                .await
+                .unwrap();
+
+        assert_eq!(outcomes.len(), 3);
+        assert!(outcomes[0].passed);
+        assert!(outcomes[1].passed);
+        assert!(!outcomes[2].passed);
+        assert!(outcomes[2]
+            .message
+            .as_deref()
+            .unwrap()
+            .contains("to contain 'pong'"));
+    }
+}
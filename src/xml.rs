@@ -0,0 +1,227 @@
+use crate::error::{Error, Result};
+use quick_xml::events::Event;
+use quick_xml::{Reader, Writer};
+use sxd_document::dom::Document;
+use sxd_document::Package;
+use sxd_xpath::{Context, Factory, Value, XPath};
+
+pub struct XmlDocument {
+    package: Package,
+    namespaces: Vec<(String, String)>,
+}
+
+impl XmlDocument {
+    pub fn parse(xml: &str) -> Result<Self> {
+        let package = sxd_document::parser::parse(xml)
+            .map_err(|e| Error::Xml(format!("Invalid XML: {:?}", e)))?;
+        Ok(Self {
+            package,
+            namespaces: Vec::new(),
+        })
+    }
+
+    pub fn namespace(mut self, prefix: impl Into<String>, uri: impl Into<String>) -> Self {
+        self.namespaces.push((prefix.into(), uri.into()));
+        self
+    }
+
+    pub fn value(&self, xpath: &str) -> Result<Option<String>> {
+        self.evaluate(xpath, |value| match value {
+            Value::Nodeset(nodes) if nodes.size() == 0 => Ok(None),
+            other => Ok(Some(other.string())),
+        })
+    }
+
+    pub fn values(&self, xpath: &str) -> Result<Vec<String>> {
+        self.evaluate(xpath, |value| match value {
+            Value::Nodeset(nodes) => Ok(nodes
+                .document_order()
+                .iter()
+                .map(|node| node.string_value())
+                .collect()),
+            other => Ok(vec![other.string()]),
+        })
+    }
+
+    pub fn count(&self, xpath: &str) -> Result<usize> {
+        self.evaluate(xpath, |value| match value {
+            Value::Nodeset(nodes) => Ok(nodes.size()),
+            _ => Err(Error::Xml(format!(
+                "XPath '{}' does not select nodes",
+                xpath
+            ))),
+        })
+    }
+
+    pub fn exists(&self, xpath: &str) -> Result<bool> {
+        self.evaluate(xpath, |value| Ok(value.boolean()))
+    }
+
+    pub fn check_xpath(&self, xpath: &str, expected: &str) -> Result<()> {
+        let actual = self.value(xpath)?.ok_or_else(|| Error::PathNotFound {
+            path: xpath.to_string(),
+        })?;
+        if actual != expected {
+            return Err(Error::FieldMismatch {
+                field: xpath.to_string(),
+                expected: format!("{:?}", expected),
+                actual: format!("{:?}", actual),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn check_xpath_count(&self, xpath: &str, expected: usize) -> Result<()> {
+        let actual = self.count(xpath)?;
+        if actual != expected {
+            return Err(Error::Assertion(format!(
+                "Expected {} nodes matching '{}', got {}",
+                expected, xpath, actual
+            )));
+        }
+        Ok(())
+    }
+
+    fn evaluate<T>(&self, xpath: &str, convert: impl FnOnce(Value) -> Result<T>) -> Result<T> {
+        let expression = compile(xpath)?;
+
+        let document = self.package.as_document();
+        let mut context = Context::new();
+        for (prefix, uri) in document_namespaces(&document) {
+            context.set_namespace(&prefix, &uri);
+        }
+        for (prefix, uri) in &self.namespaces {
+            context.set_namespace(prefix, uri);
+        }
+
+        let value = expression
+            .evaluate(&context, document.root())
+            .map_err(|e| Error::Xml(format!("Failed to evaluate XPath '{}': {}", xpath, e)))?;
+        convert(value)
+    }
+}
+
+pub fn validate_xpath(xpath: &str) -> Result<()> {
+    compile(xpath).map(drop)
+}
+
+fn compile(xpath: &str) -> Result<XPath> {
+    Factory::new()
+        .build(xpath)
+        .map_err(|e| Error::Config(format!("Invalid XPath '{}': {}", xpath, e)))?
+        .ok_or_else(|| Error::Config("Empty XPath expression".to_string()))
+}
+
+fn document_namespaces(document: &Document) -> Vec<(String, String)> {
+    document
+        .root()
+        .children()
+        .into_iter()
+        .filter_map(|child| child.element())
+        .flat_map(|element| element.namespaces_in_scope())
+        .filter(|namespace| !namespace.prefix().is_empty() && namespace.prefix() != "xml")
+        .map(|namespace| (namespace.prefix().to_string(), namespace.uri().to_string()))
+        .collect()
+}
+
+pub fn pretty_print(xml: &str) -> Result<String> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Eof) => break,
+            Ok(event) => writer.write_event(event)?,
+            Err(e) => return Err(Error::Xml(format!("Invalid XML: {}", e))),
+        }
+    }
+
+    String::from_utf8(writer.into_inner())
+        .map_err(|e| Error::Xml(format!("Invalid UTF-8 in XML: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORDERS: &str = r#"<?xml version="1.0"?>
+<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/">
+  <soap:Body>
+    <orders xmlns="urn:shop">
+      <order id="1"><total>9.50</total></order>
+      <order id="2"><total>20</total></order>
+    </orders>
+  </soap:Body>
+</soap:Envelope>"#;
+
+    #[test]
+    fn test_xpath_queries() {
+        let document = XmlDocument::parse(ORDERS)
+            .unwrap()
+            .namespace("s", "urn:shop");
+
+        assert_eq!(document.count("//s:order").unwrap(), 2);
+        assert_eq!(document.value("//s:order[2]/@id").unwrap().unwrap(), "2");
+        assert_eq!(
+            document.values("//s:order/s:total").unwrap(),
+            vec!["9.50", "20"]
+        );
+        assert_eq!(
+            document.value("sum(//s:total)").unwrap().as_deref(),
+            Some("29.5")
+        );
+        assert_eq!(document.value("//s:missing").unwrap(), None);
+        assert!(document.exists("/soap:Envelope/soap:Body").unwrap());
+        assert!(!document.exists("//order").unwrap());
+        assert!(document.count("count(//s:order)").is_err());
+        assert!(document.value("//[").is_err());
+        assert!(XmlDocument::parse("<open>").is_err());
+    }
+
+    #[test]
+    fn test_pretty_print() {
+        assert_eq!(
+            pretty_print(r#"<a><b id="1">text</b><c/></a>"#).unwrap(),
+            "<a>\n  <b id=\"1\">text</b>\n  <c/>\n</a>"
+        );
+    }
+
+    #[test]
+    fn test_soap_exchange() {
+        use crate::mock::{MockServer, Stub};
+        use crate::request::Request;
+
+        let server = MockServer::start().unwrap();
+        server.mock(
+            Stub::post("/orders")
+                .header("SOAPAction", "\"urn:shop/ListOrders\"")
+                .body_contains("<ListOrders/>")
+                .response_header("Content-Type", "text/xml; charset=utf-8")
+                .response_body(ORDERS),
+        );
+
+        let envelope = r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/"><soap:Body><ListOrders/></soap:Body></soap:Envelope>"#;
+        let response = Request::post(server.url("/orders"))
+            .soap("urn:shop/ListOrders", envelope)
+            .send()
+            .unwrap()
+            .expect_xml()
+            .unwrap()
+            .xml_namespace("s", "urn:shop")
+            .assert_xpath("//s:order[1]/s:total", "9.50")
+            .unwrap()
+            .assert_xpath_count("/soap:Envelope/soap:Body//s:order", 2)
+            .unwrap();
+
+        let document = response.xml().unwrap();
+        let error = document.check_xpath("//s:order[1]/@id", "2").unwrap_err();
+        assert!(matches!(error, Error::FieldMismatch { .. }));
+        let error = document.check_xpath("//s:refund", "1").unwrap_err();
+        assert!(matches!(error, Error::PathNotFound { path } if path == "//s:refund"));
+        assert!(matches!(
+            document.check_xpath("//[", "1"),
+            Err(Error::Config(_))
+        ));
+    }
+}